@@ -0,0 +1,233 @@
+use proc_macro2::{Delimiter, Group, Span, TokenStream, TokenTree};
+use quote::{quote, quote_spanned};
+
+/*
+ec! reads easy_config syntax out of Rust tokens:
+
+sequence -> expression*
+expression -> doc_comment* ( list | interpolation | presence | binding )
+list -> "(" sequence ")"
+interpolation -> "{" RUST_EXPRESSION "}"
+presence -> atom
+binding -> atom "=" expression list?
+doc_comment -> "///" TEXT
+
+Rust splits text such as `127.0.0.1` or `src/content` into several tokens, so an atom is every
+token up to the next whitespace, exactly as the easy_config lexer would see it. String literals
+are always an atom of their own, which gives a way to write characters Rust can't tokenize.
+*/
+
+struct Atom {
+    text: String,
+    quoted: bool,
+    span: Span,
+}
+
+struct Cursor {
+    tokens: Vec<TokenTree>,
+    index: usize,
+}
+
+fn line_column(span: proc_macro::Span) -> (usize, usize) {
+    (span.line(), span.column())
+}
+
+// Whether `next` starts exactly where `previous` ends, i.e. there is no whitespace between them.
+fn adjacent(previous: &TokenTree, next: &TokenTree) -> bool {
+    line_column(previous.span().unwrap().end()) == line_column(next.span().unwrap().start())
+}
+
+fn string_literal(token: &TokenTree) -> Option<String> {
+    let TokenTree::Literal(literal) = token else { return None };
+    match syn::Lit::new(literal.clone()) {
+        syn::Lit::Str(s) => Some(s.value()),
+        _ => None,
+    }
+}
+
+fn is_punct(token: &TokenTree, c: char) -> bool {
+    matches!(token, TokenTree::Punct(p) if p.as_char() == c)
+}
+
+fn ends_atom(token: &TokenTree) -> bool {
+    matches!(token, TokenTree::Group(_)) ||
+    is_punct(token, '=') ||
+    is_punct(token, '#') ||
+    string_literal(token).is_some()
+}
+
+impl Cursor {
+    fn new(tokens: TokenStream) -> Self {
+        Self {
+            tokens: tokens.into_iter().collect(),
+            index: 0,
+        }
+    }
+
+    fn finished(&self) -> bool {
+        self.index >= self.tokens.len()
+    }
+
+    fn peek(&self) -> Option<&TokenTree> {
+        self.tokens.get(self.index)
+    }
+
+    fn next(&mut self) -> Option<TokenTree> {
+        let token = self.tokens.get(self.index).cloned();
+        self.index += 1;
+        token
+    }
+
+    fn sequence(&mut self) -> syn::Result<Vec<TokenStream>> {
+        let mut expressions = vec![];
+        while !self.finished() {
+            expressions.push(self.expression()?);
+        }
+        Ok(expressions)
+    }
+
+    fn doc_comments(&mut self) -> syn::Result<Vec<String>> {
+        let mut comments = vec![];
+        while self.peek().is_some_and(|x| is_punct(x, '#')) {
+            let hash = self.next().unwrap();
+            let Some(TokenTree::Group(attribute)) = self.next() else {
+                return Err(syn::Error::new(hash.span(), "expected a doc comment"))
+            };
+
+            let meta: syn::MetaNameValue = syn::parse2(attribute.stream())?;
+            let syn::Expr::Lit(syn::ExprLit { lit: syn::Lit::Str(comment), .. }) = meta.value else {
+                return Err(syn::Error::new(attribute.span(), "expected a doc comment"))
+            };
+
+            let comment = comment.value();
+            comments.push(comment.strip_prefix(' ').unwrap_or(&comment).to_string());
+        }
+
+        Ok(comments)
+    }
+
+    fn atom(&mut self) -> syn::Result<Atom> {
+        let first = self.next().unwrap();
+        if let Some(text) = string_literal(&first) {
+            return Ok(Atom { text, quoted: true, span: first.span() })
+        }
+
+        let mut text = first.to_string();
+        let mut previous = first;
+        while let Some(next) = self.peek() {
+            if ends_atom(next) || !adjacent(&previous, next) {
+                break
+            }
+
+            text.push_str(&next.to_string());
+            previous = self.next().unwrap();
+        }
+
+        Ok(Atom { text, quoted: false, span: previous.span() })
+    }
+
+    fn list(&mut self, group: &Group) -> syn::Result<TokenStream> {
+        let elements = Cursor::new(group.stream()).sequence()?;
+        Ok(quote_spanned! { group.span() =>
+            ::easy_config::expression::Expression::list(vec![#(#elements),*])
+        })
+    }
+
+    fn expression(&mut self) -> syn::Result<TokenStream> {
+        let comments = self.doc_comments()?;
+
+        let Some(token) = self.peek().cloned() else {
+            let span = self.tokens.last().map(|x| x.span()).unwrap_or(Span::call_site());
+            return Err(syn::Error::new(span, "expected an expression after the doc comment"))
+        };
+
+        let expression = match &token {
+            TokenTree::Group(group) => {
+                self.next();
+                match group.delimiter() {
+                    Delimiter::Parenthesis => self.list(group)?,
+                    Delimiter::Brace => {
+                        let value = group.stream();
+                        quote_spanned! { group.span() =>
+                            ::easy_config::serialization::EasyConfig::serialize(&(#value))
+                        }
+                    }
+                    _ => return Err(syn::Error::new(
+                        group.span(),
+                        "expected a list in parentheses, or a Rust expression in braces to interpolate"
+                    )),
+                }
+            }
+            _ if is_punct(&token, '=') => return Err(syn::Error::new(
+                token.span(),
+                "expected a name before '='"
+            )),
+            _ => self.presence_or_binding()?,
+        };
+
+        if comments.is_empty() {
+            Ok(expression)
+        } else {
+            let comment = comments.join("\n");
+            Ok(quote! { #expression.with_comment(#comment) })
+        }
+    }
+
+    fn presence_or_binding(&mut self) -> syn::Result<TokenStream> {
+        let atom = self.atom()?;
+
+        if !self.peek().is_some_and(|x| is_punct(x, '=')) {
+            return Ok(presence(atom))
+        }
+
+        let equals = self.next().unwrap();
+        if self.finished() {
+            return Err(syn::Error::new(equals.span(), "expected a value after '='"))
+        }
+        let mut value = self.expression()?;
+
+        // Like the parser, `name = Some(thing)` folds `Some` into the list that follows it.
+        if let Some(TokenTree::Group(group)) = self.peek().cloned() {
+            if group.delimiter() == Delimiter::Parenthesis {
+                self.next();
+                let list = self.list(&group)?;
+                value = quote! {
+                    {
+                        let mut list = #list;
+                        list.prepend_into_list(#value);
+                        list
+                    }
+                };
+            }
+        }
+
+        let name = atom.text;
+        Ok(quote_spanned! { atom.span =>
+            ::easy_config::expression::Expression::binding(#name, #value)
+        })
+    }
+}
+
+fn presence(atom: Atom) -> TokenStream {
+    let text = atom.text;
+    let atom_tokens = if atom.quoted {
+        quote! { ::easy_config::expression::Atom::Quoted(String::from(#text)) }
+    } else {
+        quote! { ::easy_config::expression::Atom::classify(#text) }
+    };
+
+    quote_spanned! { atom.span => ::easy_config::expression::Expression::presence(#atom_tokens) }
+}
+
+pub fn expand(input: TokenStream) -> TokenStream {
+    let expressions = match Cursor::new(input).sequence() {
+        Ok(e) => e,
+        Err(e) => return e.to_compile_error(),
+    };
+
+    if expressions.len() == 1 {
+        expressions.into_iter().next().unwrap()
+    } else {
+        quote! { ::easy_config::expression::Expression::list(vec![#(#expressions),*]) }
+    }
+}
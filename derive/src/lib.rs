@@ -1,6 +1,7 @@
 mod serialize_helpers;
 mod deserialize_helpers;
 mod shared;
+mod ec;
 
 use proc_macro::TokenStream;
 use quote::quote;
@@ -59,6 +60,35 @@ fn generate_config_for_struct(input: &DeriveInput, data: &DataStruct) -> TokenSt
     }.into()
 }
 
+// Enums whose variants are all units can be map keys, written as the variant's name.
+fn generate_map_key_for_enum(input: &DeriveInput, data: &DataEnum) -> proc_macro2::TokenStream {
+    if data.variants.is_empty() || data.variants.iter().any(|x| !matches!(x.fields, syn::Fields::Unit)) {
+        return quote! {}
+    }
+
+    let enum_name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = generate_impl_generics(&input.generics);
+    let idents = data.variants.iter().map(|x| &x.ident).collect::<Vec<_>>();
+    let names = idents.iter().map(|x| x.to_string()).collect::<Vec<_>>();
+
+    quote! {
+        impl #impl_generics ::easy_config::serialization::map_key::MapKey for #enum_name #ty_generics #where_clause {
+            fn to_key(&self) -> String {
+                match self {
+                    #(#enum_name::#idents => #names,)*
+                }.to_string()
+            }
+
+            fn from_key(key: &str) -> Option<Self> {
+                match key {
+                    #(#names => Some(#enum_name::#idents),)*
+                    _ => None,
+                }
+            }
+        }
+    }
+}
+
 fn generate_config_for_enum(input: &DeriveInput, data: &DataEnum) -> TokenStream {
     let enum_name = &input.ident;
     let enum_name_str = enum_name.to_string();
@@ -83,7 +113,11 @@ fn generate_config_for_enum(input: &DeriveInput, data: &DataEnum) -> TokenStream
         enum_name_str
     );
 
+    let map_key = generate_map_key_for_enum(input, data);
+
     quote! {
+        #map_key
+
         impl #impl_generics ::easy_config::serialization::EasyConfig for #enum_name #ty_generics #where_clause {
             fn serialize(&self) -> ::easy_config::expression::Expression {
                 match self {
@@ -130,4 +164,23 @@ pub fn derive_config(input: TokenStream) -> TokenStream {
                 .into()
         },
     }
-}
\ No newline at end of file
+}
+
+/// Build an `Expression` from easy_config syntax at compile time. Rust values can be interpolated
+/// with braces, in which case they are serialized with `EasyConfig::serialize`, and doc comments
+/// become comments on the expression that follows them. A single expression is returned as is,
+/// while several are wrapped in a list.
+///
+/// ```ignore
+/// let port = 8080_u16;
+/// let expr = ec! {
+///     /// Where to listen.
+///     address = 127.0.0.1
+///     port = {port}
+///     addresses = (None (IpV4 (127.0.0.1)))
+/// };
+/// ```
+#[proc_macro]
+pub fn ec(input: TokenStream) -> TokenStream {
+    ec::expand(input.into()).into()
+}
@@ -10,16 +10,16 @@ pub fn serialize_named_field(accessor: impl ToTokens, field: &Field) -> proc_mac
     let uncommented = quote! {
         ::easy_config::expression::Expression::binding(
             #field_name,
-            #accessor #field_ident.serialize()
+            ::easy_config::serialization::EasyConfig::serialize(&#accessor #field_ident)
         )
     };
-    append_comment(uncommented, &field)
+    append_comment(uncommented, field)
 }
 pub fn serialize_named_fields(prefix: impl ToTokens, fields_named: &FieldsNamed) -> proc_macro2::TokenStream {
     let prefix = prefix.into_token_stream();
 
     let entries = fields_named.named.iter().map(|field| {
-        serialize_named_field(prefix.clone(), &field)
+        serialize_named_field(prefix.clone(), field)
     });
 
     serialize_into_list(entries)
@@ -29,7 +29,7 @@ pub fn serialize_unnamed_fields(prefix: impl ToTokens, fields_unnamed: &FieldsUn
     let entries = fields_unnamed.unnamed.iter().enumerate().map(|(index, field)| {
         let index = syn::Index::from(index);
         append_comment(quote! {
-            #prefix #index.serialize()
+            ::easy_config::serialization::EasyConfig::serialize(&#prefix #index)
         }, field)
     });
 
@@ -106,7 +106,7 @@ fn serialize_unnamed_variant(fields_unnamed: &FieldsUnnamed) -> proc_macro2::Tok
         let field_var = syn::Ident::new(&format!("f{}", index), proc_macro2::Span::call_site());
         let field = &fields_unnamed.unnamed[index];
         append_comment(quote! {
-            #field_var.serialize()
+            ::easy_config::serialization::EasyConfig::serialize(#field_var)
         }, field)
     });
 
@@ -115,7 +115,7 @@ fn serialize_unnamed_variant(fields_unnamed: &FieldsUnnamed) -> proc_macro2::Tok
 pub fn serialize_variant_arm(enum_name: &Ident, variant: &Variant) -> proc_macro2::TokenStream {
 
     let fields = match &variant.fields {
-        Fields::Named(named) => serialize_named_fields(quote! {}, named),
+        Fields::Named(named) => serialize_named_fields(quote! {*}, named),
         Fields::Unnamed(unnamed) => serialize_unnamed_variant(unnamed),
         Fields::Unit => serialize_variant_with_no_fields()
     };
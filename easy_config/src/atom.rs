@@ -0,0 +1,736 @@
+use std::cmp::Ordering;
+use std::fmt::Display;
+use std::hash::{Hash, Hasher};
+use crate::lexer;
+
+#[derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash)]
+pub enum Radix {
+    Binary,
+    Octal,
+    Decimal,
+    Hexadecimal,
+}
+
+impl Radix {
+    pub fn base(&self) -> u32 {
+        match self {
+            Radix::Binary => 2,
+            Radix::Octal => 8,
+            Radix::Decimal => 10,
+            Radix::Hexadecimal => 16,
+        }
+    }
+
+    pub fn prefix(&self) -> &'static str {
+        match self {
+            Radix::Binary => "0b",
+            Radix::Octal => "0o",
+            Radix::Decimal => "",
+            Radix::Hexadecimal => "0x",
+        }
+    }
+}
+
+/// An integer literal. The magnitude is kept apart from the sign so that every literal from
+/// `-u128::MAX` to `u128::MAX` can be represented, and the radix is kept so the literal can be
+/// written back the way it was read.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct Integer {
+    negative: bool,
+    // Split in two halves, because a u128 field would force 16 byte alignment onto every
+    // `Expression`.
+    magnitude: [u64; 2],
+    radix: Radix,
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+enum IntegerError {
+    Invalid,
+    Overflow,
+}
+
+impl Integer {
+    pub fn new(negative: bool, magnitude: u128, radix: Radix) -> Self {
+        Self {
+            // There is no such thing as negative zero for integers.
+            negative: negative && magnitude != 0,
+            magnitude: [(magnitude >> 64) as u64, magnitude as u64],
+            radix,
+        }
+    }
+
+    pub fn is_negative(&self) -> bool {
+        self.negative
+    }
+    pub fn magnitude(&self) -> u128 {
+        ((self.magnitude[0] as u128) << 64) | self.magnitude[1] as u128
+    }
+    pub fn radix(&self) -> Radix {
+        self.radix
+    }
+
+    pub fn with_radix(mut self, radix: Radix) -> Self {
+        self.radix = radix;
+        self
+    }
+
+    /// Convert to any primitive integer type, or `None` if the value is out of its range.
+    pub fn convert<T: TryFrom<u128> + TryFrom<i128>>(&self) -> Option<T> {
+        if self.negative {
+            T::try_from(0i128.checked_sub_unsigned(self.magnitude())?).ok()
+        } else {
+            T::try_from(self.magnitude()).ok()
+        }
+    }
+
+    pub fn to_f64(&self) -> f64 {
+        let magnitude = self.magnitude() as f64;
+        if self.negative { -magnitude } else { magnitude }
+    }
+
+    pub fn to_f32(&self) -> f32 {
+        let magnitude = self.magnitude() as f32;
+        if self.negative { -magnitude } else { magnitude }
+    }
+
+    /// Split an integer literal into its sign, its radix, and the value of each of its digits, most
+    /// significant first, or `None` if `text` isn't an integer. The literal may have any number of
+    /// digits, so this also reads integers too large for an `Integer`, which the parser leaves as
+    /// `Atom::Number`.
+    ///
+    /// Accepts an optional sign, an optional `0x`, `0o`, or `0b` prefix, and digits which may be
+    /// separated by underscores.
+    pub fn literal_digits(text: &str) -> Option<(bool, Radix, Vec<u8>)> {
+        let (negative, unsigned) = match text.strip_prefix('-') {
+            Some(rest) => (true, rest),
+            None => (false, text.strip_prefix('+').unwrap_or(text)),
+        };
+
+        let (radix, digits) = match unsigned.get(..2) {
+            Some("0x") | Some("0X") => (Radix::Hexadecimal, &unsigned[2..]),
+            Some("0o") | Some("0O") => (Radix::Octal, &unsigned[2..]),
+            Some("0b") | Some("0B") => (Radix::Binary, &unsigned[2..]),
+            _ => (Radix::Decimal, unsigned),
+        };
+
+        if digits.is_empty() || digits.starts_with('_') || digits.ends_with('_') {
+            return None
+        }
+
+        let digits = digits.chars()
+            .filter(|x| *x != '_')
+            .map(|c| c.to_digit(radix.base()).map(|x| x as u8))
+            .collect::<Option<Vec<_>>>()?;
+        Some((negative, radix, digits))
+    }
+
+    fn parse(text: &str) -> Result<Integer, IntegerError> {
+        let (negative, radix, digits) = Integer::literal_digits(text).ok_or(IntegerError::Invalid)?;
+        let base = radix.base() as u128;
+        digits.into_iter()
+            .try_fold(0u128, |x, digit| x.checked_mul(base)?.checked_add(digit as u128))
+            .map(|x| Integer::new(negative, x, radix))
+            .ok_or(IntegerError::Overflow)
+    }
+}
+
+impl Display for Integer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let sign = if self.negative { "-" } else { "" };
+        let prefix = self.radix.prefix();
+        match self.radix {
+            Radix::Binary => write!(f, "{}{}{:b}", sign, prefix, self.magnitude()),
+            Radix::Octal => write!(f, "{}{}{:o}", sign, prefix, self.magnitude()),
+            Radix::Decimal => write!(f, "{}{}", sign, self.magnitude()),
+            Radix::Hexadecimal => write!(f, "{}{}{:x}", sign, prefix, self.magnitude()),
+        }
+    }
+}
+
+impl PartialOrd for Integer {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+impl Ord for Integer {
+    // Numeric order first, so that sorted integers read the way a person would expect.
+    fn cmp(&self, other: &Self) -> Ordering {
+        let by_value = match (self.negative, other.negative) {
+            (false, false) => self.magnitude().cmp(&other.magnitude()),
+            (true, true) => other.magnitude().cmp(&self.magnitude()),
+            (negative, _) => other.negative.cmp(&negative),
+        };
+        by_value.then(self.radix.cmp(&other.radix))
+    }
+}
+
+macro_rules! signed_integer {
+    ($ty:ty) => {
+        impl From<$ty> for Integer {
+            fn from(value: $ty) -> Self {
+                Integer::new(value < 0, (value as i128).unsigned_abs(), Radix::Decimal)
+            }
+        }
+    };
+}
+
+macro_rules! unsigned_integer {
+    ($ty:ty) => {
+        impl From<$ty> for Integer {
+            fn from(value: $ty) -> Self {
+                Integer::new(false, value as u128, Radix::Decimal)
+            }
+        }
+    };
+}
+
+signed_integer!(i8);
+signed_integer!(i16);
+signed_integer!(i32);
+signed_integer!(i64);
+signed_integer!(i128);
+signed_integer!(isize);
+
+unsigned_integer!(u8);
+unsigned_integer!(u16);
+unsigned_integer!(u32);
+unsigned_integer!(u64);
+unsigned_integer!(u128);
+unsigned_integer!(usize);
+
+/// A floating point literal. The literal text is kept next to the parsed value, so it can be
+/// parsed again at a different precision without rounding twice.
+#[derive(Debug, Clone)]
+pub struct Float {
+    value: f64,
+    literal: String,
+}
+
+impl Float {
+    pub fn value(&self) -> f64 {
+        self.value
+    }
+
+    pub fn literal(&self) -> &str {
+        &self.literal
+    }
+
+    pub fn to_f32(&self) -> f32 {
+        self.literal.parse().unwrap_or(self.value as f32)
+    }
+
+    // Only literals with a fractional part or an exponent, and the special values, are floats.
+    // Anything else is either an integer or not a number at all.
+    fn parse(text: &str) -> Option<Float> {
+        if let Some(value) = special_value(text) {
+            return Some(Float { value, literal: text.to_string() })
+        }
+
+        let unsigned = text.strip_prefix(['-', '+']).unwrap_or(text);
+        if !unsigned.starts_with(|c: char| c.is_ascii_digit()) {
+            return None
+        }
+        if !unsigned.contains(['.', 'e', 'E']) {
+            return None
+        }
+
+        Some(Float {
+            value: text.parse().ok()?,
+            literal: text.to_string(),
+        })
+    }
+}
+
+// Floats which aren't finite are spelled `nan`, `inf`, and `-inf`. A `+inf` is read, but never
+// written.
+fn special_value(text: &str) -> Option<f64> {
+    match text {
+        "nan" => Some(f64::NAN),
+        "inf" | "+inf" => Some(f64::INFINITY),
+        "-inf" => Some(f64::NEG_INFINITY),
+        _ => None,
+    }
+}
+
+// The sign and payload of a NaN are not kept, since there is no way to write them.
+fn special_literal(value: f64) -> Option<&'static str> {
+    if value.is_nan() {
+        Some("nan")
+    } else if value == f64::INFINITY {
+        Some("inf")
+    } else if value == f64::NEG_INFINITY {
+        Some("-inf")
+    } else {
+        None
+    }
+}
+
+impl From<f64> for Float {
+    fn from(value: f64) -> Self {
+        // Debug formatting is the shortest text which parses back to exactly `value`, and keeps
+        // the sign of `-0.0`.
+        let literal = special_literal(value)
+            .map(str::to_string)
+            .unwrap_or_else(|| format!("{:?}", value));
+        Float { value, literal }
+    }
+}
+
+impl From<f32> for Float {
+    fn from(value: f32) -> Self {
+        let literal = special_literal(value as f64)
+            .map(str::to_string)
+            .unwrap_or_else(|| format!("{:?}", value));
+        Float {
+            value: literal.parse().unwrap(),
+            literal,
+        }
+    }
+}
+
+impl PartialEq for Float {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+impl Eq for Float {}
+
+impl PartialOrd for Float {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+impl Ord for Float {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.value.total_cmp(&other.value)
+    }
+}
+
+impl Hash for Float {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.value.to_bits().hash(state)
+    }
+}
+
+impl Display for Float {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.literal)
+    }
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub(crate) enum QuoteError {
+    Unterminated,
+    InvalidUnicodeEscape,
+}
+
+/// Wrap `text` in double quotes, escaping anything which would end the string early or which is
+/// not visible.
+pub(crate) fn quote(text: &str) -> String {
+    let mut quoted = String::with_capacity(text.len() + 2);
+    quoted.push('"');
+    for c in text.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\t' => quoted.push_str("\\t"),
+            '\r' => quoted.push_str("\\r"),
+            '\0' => quoted.push_str("\\0"),
+            c if c.is_control() => quoted.push_str(&format!("\\u{{{:x}}}", c as u32)),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+/// The inverse of `quote`. `lexeme` must include the surrounding quotes.
+pub(crate) fn unquote(lexeme: &str) -> Result<String, QuoteError> {
+    let mut chars = lexeme.strip_prefix('"').ok_or(QuoteError::Unterminated)?.chars();
+    let mut text = String::with_capacity(lexeme.len());
+
+    while let Some(c) = chars.next() {
+        match c {
+            '"' => return if chars.next().is_none() { Ok(text) } else { Err(QuoteError::Unterminated) },
+            '\\' => match chars.next().ok_or(QuoteError::Unterminated)? {
+                'n' => text.push('\n'),
+                't' => text.push('\t'),
+                'r' => text.push('\r'),
+                '0' => text.push('\0'),
+                'u' => text.push(unicode_escape(&mut chars)?),
+                other => text.push(other),
+            },
+            c => text.push(c),
+        }
+    }
+
+    Err(QuoteError::Unterminated)
+}
+
+// Reads the `{XXXX}` part of a `\u{XXXX}` escape.
+fn unicode_escape(chars: &mut std::str::Chars) -> Result<char, QuoteError> {
+    if chars.next() != Some('{') {
+        return Err(QuoteError::InvalidUnicodeEscape)
+    }
+
+    let mut hex = String::new();
+    for c in chars.by_ref() {
+        if c == '}' {
+            return u32::from_str_radix(&hex, 16)
+                .ok()
+                .and_then(char::from_u32)
+                .ok_or(QuoteError::InvalidUnicodeEscape)
+        }
+        hex.push(c);
+    }
+
+    Err(QuoteError::InvalidUnicodeEscape)
+}
+
+/// Decode the backslash escapes of a bare token, so `\(` stands for `(`. A backslash at the very
+/// end of the input has nothing to escape and is kept.
+pub(crate) fn unescape(lexeme: &str) -> String {
+    let mut text = String::with_capacity(lexeme.len());
+    let mut chars = lexeme.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => text.push(chars.next().unwrap_or('\\')),
+            c => text.push(c),
+        }
+    }
+    text
+}
+
+/// The inverse of `unescape`. Anything which would end the token, or start a quoted string, is
+/// preceded by a backslash. The `=` of an inclusive range such as `..=5` is left alone, since the
+/// lexer keeps it in the token. Text which would otherwise read as another kind of atom, such as
+/// `true` or `-1`, starts with a backslash, since escaped tokens are always text.
+fn escape(text: &str) -> String {
+    let reads_as_other = !Atom::classify(text).is_text();
+    let mut escaped = String::with_capacity(text.len() + 1);
+    for (index, c) in text.char_indices() {
+        let inclusive_range = c == '=' && text[..index].ends_with("..") && text[index + 1..].starts_with(|x: char| x.is_numeric() || x == '-');
+        if c == '\\' || c.is_whitespace() || (lexer::STOPPING_CHARS.contains(&c) && !inclusive_range) || (index == 0 && (c == '"' || reads_as_other)) {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+#[derive(Debug, Clone, Ord, PartialOrd, Eq, PartialEq, Hash)]
+pub enum Atom {
+    /// A bare word, with any backslash escapes already decoded.
+    Text(String),
+    /// A token which starts like a number but is neither an integer nor a float, such as
+    /// `1.2.3.4`, `30s`, or an integer too large for a `u128`. It is kept verbatim.
+    Number(String),
+    Integer(Integer),
+    Float(Float),
+    Bool(bool),
+    /// The contents of a double quoted string, with escapes already decoded.
+    Quoted(String),
+}
+
+fn starts_like_number(text: &str) -> bool {
+    text.starts_with(|c: char| c.is_numeric() || c == '-')
+}
+
+impl Atom {
+    pub fn is_text(&self) -> bool {
+        matches!(self, Self::Text(_))
+    }
+    pub fn is_number(&self) -> bool {
+        matches!(self, Self::Number(_) | Self::Integer(_) | Self::Float(_))
+    }
+    pub fn is_integer(&self) -> bool {
+        matches!(self, Self::Integer(_))
+    }
+    pub fn is_float(&self) -> bool {
+        matches!(self, Self::Float(_))
+    }
+    pub fn is_bool(&self) -> bool {
+        matches!(self, Self::Bool(_))
+    }
+    pub fn is_quoted(&self) -> bool {
+        matches!(self, Self::Quoted(_))
+    }
+
+    /// The atom the parser reads from a bare token. A token with escapes in it is always text, so
+    /// that `\-1` can be used to write the word `-1`.
+    pub fn from_token(lexeme: impl AsRef<str>) -> Atom {
+        let lexeme = lexeme.as_ref();
+        if lexeme.contains('\\') {
+            Atom::Text(unescape(lexeme))
+        } else {
+            Atom::classify(lexeme)
+        }
+    }
+
+    /// Work out the type of an unquoted token the same way the parser does.
+    pub fn classify(text: impl AsRef<str>) -> Atom {
+        let text = text.as_ref();
+        match text {
+            "true" => return Atom::Bool(true),
+            "false" => return Atom::Bool(false),
+            _ => {}
+        }
+
+        if special_value(text).is_some() {
+            return Float::parse(text).map(Atom::Float).expect("a special value is a float")
+        }
+        if !starts_like_number(text) {
+            return Atom::Text(text.to_string())
+        }
+
+        match Integer::parse(text) {
+            Ok(integer) => Atom::Integer(integer),
+            Err(IntegerError::Overflow) => Atom::Number(text.to_string()),
+            Err(IntegerError::Invalid) => Float::parse(text)
+                .map(Atom::Float)
+                .unwrap_or(Atom::Number(text.to_string())),
+        }
+    }
+
+    /// An atom which reads back as exactly `text`. Plain words are left bare, while anything which
+    /// the lexer would split, or the parser would read as something other than text, is quoted.
+    pub fn string(text: impl Into<String>) -> Atom {
+        let text = text.into();
+        let plain = !text.is_empty() &&
+            !text.starts_with('"') &&
+            !text.contains(|c: char| c.is_whitespace() || c == '\\' || lexer::STOPPING_CHARS.contains(&c)) &&
+            Atom::classify(&text).is_text();
+
+        if plain {
+            Atom::Text(text)
+        } else {
+            Atom::Quoted(text)
+        }
+    }
+
+    /// The text this atom stands for. Unlike `to_string`, quoted strings are not quoted.
+    pub fn to_text(&self) -> String {
+        match self {
+            Atom::Text(s) | Atom::Number(s) | Atom::Quoted(s) => s.clone(),
+            other => other.to_string(),
+        }
+    }
+}
+
+impl Display for Atom {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Atom::Text(s) => write!(f, "{}", escape(s)),
+            Atom::Number(s) => write!(f, "{}", s),
+            Atom::Integer(i) => write!(f, "{}", i),
+            Atom::Float(x) => write!(f, "{}", x),
+            Atom::Bool(b) => write!(f, "{}", b),
+            Atom::Quoted(s) => write!(f, "{}", quote(s)),
+        }
+    }
+}
+// Strings are read the way the parser reads a bare token, so `Expression::presence("2")` is the
+// integer it is written as.
+impl From<String> for Atom {
+    fn from(s: String) -> Self {
+        Atom::classify(s)
+    }
+}
+impl From<&str> for Atom {
+    fn from(s: &str) -> Self {
+        Atom::classify(s)
+    }
+}
+impl From<bool> for Atom {
+    fn from(b: bool) -> Self {
+        Atom::Bool(b)
+    }
+}
+impl From<Integer> for Atom {
+    fn from(i: Integer) -> Self {
+        Atom::Integer(i)
+    }
+}
+impl From<Float> for Atom {
+    fn from(f: Float) -> Self {
+        Atom::Float(f)
+    }
+}
+
+macro_rules! numeric_into_atom {
+    ($ty:ty, $variant:ident) => {
+        impl From<$ty> for Atom {
+            fn from(value: $ty) -> Atom {
+                Atom::$variant(value.into())
+            }
+        }
+    };
+}
+
+numeric_into_atom!(u8, Integer);
+numeric_into_atom!(u16, Integer);
+numeric_into_atom!(u32, Integer);
+numeric_into_atom!(u64, Integer);
+numeric_into_atom!(u128, Integer);
+numeric_into_atom!(usize, Integer);
+
+numeric_into_atom!(i8, Integer);
+numeric_into_atom!(i16, Integer);
+numeric_into_atom!(i32, Integer);
+numeric_into_atom!(i64, Integer);
+numeric_into_atom!(i128, Integer);
+numeric_into_atom!(isize, Integer);
+
+numeric_into_atom!(f32, Float);
+numeric_into_atom!(f64, Float);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn classify_text() {
+        assert_eq!(Atom::classify("hello"), Atom::Text("hello".to_string()));
+        assert_eq!(Atom::classify("True"), Atom::Text("True".to_string()));
+    }
+
+    #[test]
+    fn classify_bool() {
+        assert_eq!(Atom::classify("true"), Atom::Bool(true));
+        assert_eq!(Atom::classify("false"), Atom::Bool(false));
+    }
+
+    #[test]
+    fn classify_integers() {
+        assert_eq!(Atom::classify("42"), Atom::Integer(Integer::new(false, 42, Radix::Decimal)));
+        assert_eq!(Atom::classify("-42"), Atom::Integer(Integer::new(true, 42, Radix::Decimal)));
+        assert_eq!(Atom::classify("0xFF"), Atom::Integer(Integer::new(false, 255, Radix::Hexadecimal)));
+        assert_eq!(Atom::classify("0o17"), Atom::Integer(Integer::new(false, 15, Radix::Octal)));
+        assert_eq!(Atom::classify("-0b101"), Atom::Integer(Integer::new(true, 5, Radix::Binary)));
+        assert_eq!(Atom::classify("1_000_000"), Atom::Integer(Integer::new(false, 1_000_000, Radix::Decimal)));
+    }
+
+    #[test]
+    fn classify_floats() {
+        assert_eq!(Atom::classify("1.5"), Atom::Float(Float::from(1.5)));
+        assert_eq!(Atom::classify("-2e3"), Atom::Float(Float::from(-2000.0)));
+        assert_eq!(Atom::classify("1.5").to_string(), "1.5");
+    }
+
+    #[test]
+    fn special_floats() {
+        assert!(matches!(Atom::classify("nan"), Atom::Float(f) if f.value().is_nan()));
+        assert_eq!(Atom::classify("inf"), Atom::Float(Float::from(f64::INFINITY)));
+        assert_eq!(Atom::classify("+inf"), Atom::Float(Float::from(f64::INFINITY)));
+        assert_eq!(Atom::classify("-inf"), Atom::Float(Float::from(f64::NEG_INFINITY)));
+        assert_eq!(Atom::classify("NaN"), Atom::Text("NaN".to_string()));
+
+        for (value, text) in [(f64::NAN, "nan"), (-f64::NAN, "nan"), (f64::INFINITY, "inf"), (f64::NEG_INFINITY, "-inf"), (-0.0, "-0.0")] {
+            assert_eq!(Atom::from(value).to_string(), text);
+        }
+        assert_eq!(Atom::from(f32::NEG_INFINITY).to_string(), "-inf");
+        assert_eq!(Atom::string("inf"), Atom::Quoted("inf".to_string()));
+    }
+
+    #[test]
+    fn classify_other_numbers() {
+        assert_eq!(Atom::classify("1.2.3.4"), Atom::Number("1.2.3.4".to_string()));
+        assert_eq!(Atom::classify("30s"), Atom::Number("30s".to_string()));
+        assert_eq!(Atom::classify("0x"), Atom::Number("0x".to_string()));
+        assert_eq!(Atom::classify("1_"), Atom::Number("1_".to_string()));
+    }
+
+    #[test]
+    fn huge_integers_stay_numbers() {
+        let huge = "340282366920938463463374607431768211456";
+        assert_eq!(Atom::classify(huge), Atom::Number(huge.to_string()));
+        let max = "340282366920938463463374607431768211455";
+        assert_eq!(Atom::classify(max), Atom::Integer(Integer::from(u128::MAX)));
+    }
+
+    #[test]
+    fn literal_digits() {
+        assert_eq!(Integer::literal_digits("-0x1_F"), Some((true, Radix::Hexadecimal, vec![1, 15])));
+        assert_eq!(Integer::literal_digits("+0b10").map(|x| x.2.len()), Some(2));
+        assert_eq!(Integer::literal_digits(&"9".repeat(50)).map(|x| x.2.len()), Some(50));
+        for invalid in ["0x", "1_", "_1", "0b12", "1.5", "cat"] {
+            assert_eq!(Integer::literal_digits(invalid), None, "{}", invalid);
+        }
+    }
+
+    #[test]
+    fn integer_display_keeps_radix() {
+        for text in ["42", "-42", "0xff", "0o17", "-0b101"] {
+            assert_eq!(Atom::classify(text).to_string(), text);
+        }
+    }
+
+    #[test]
+    fn convert() {
+        let small = Integer::from(200_u8);
+        assert_eq!(small.convert::<u8>(), Some(200));
+        assert_eq!(small.convert::<i8>(), None);
+        assert_eq!(Integer::from(i128::MIN).convert::<i128>(), Some(i128::MIN));
+        assert_eq!(Integer::from(-1).convert::<u64>(), None);
+        assert_eq!(Integer::new(true, u128::MAX, Radix::Decimal).convert::<i128>(), None);
+    }
+
+    #[test]
+    fn integers_sort_numerically() {
+        let mut sorted = vec![Integer::from(3), Integer::from(-10), Integer::from(0), Integer::from(-1)];
+        sorted.sort();
+        assert_eq!(sorted, vec![Integer::from(-10), Integer::from(-1), Integer::from(0), Integer::from(3)]);
+    }
+
+    #[test]
+    fn f32_literals_are_exact() {
+        let float = Float::from(0.1_f32);
+        assert_eq!(float.literal(), "0.1");
+        assert_eq!(float.to_f32(), 0.1_f32);
+    }
+
+    #[test]
+    fn quote_round_trip() {
+        for text in ["", "plain", "with space", "quote \" inside", "back\\slash", "new\nline\ttab", "bell\u{7}"] {
+            assert_eq!(unquote(&quote(text)).unwrap(), text);
+        }
+    }
+
+    #[test]
+    fn unquote_errors() {
+        assert_eq!(unquote("\"open"), Err(QuoteError::Unterminated));
+        assert_eq!(unquote("\"ends in escape\\\""), Err(QuoteError::Unterminated));
+        assert_eq!(unquote("\"\\u{110000}\""), Err(QuoteError::InvalidUnicodeEscape));
+        assert_eq!(unquote("\"\\u{e9}\""), Ok("é".to_string()));
+    }
+
+    #[test]
+    fn escaped_tokens() {
+        assert_eq!(Atom::from_token(r"a\(b\)"), Atom::Text("a(b)".to_string()));
+        assert_eq!(Atom::from_token(r"C:\\dir"), Atom::Text(r"C:\dir".to_string()));
+        assert_eq!(Atom::from_token(r"\-1"), Atom::Text("-1".to_string()));
+        assert_eq!(Atom::from_token("-1"), Atom::classify("-1"));
+        assert_eq!(Atom::Text("a (b)=c".to_string()).to_string(), r"a\ \(b\)\=c");
+        assert_eq!(Atom::Text(r"\\".to_string()).to_string(), r"\\\\");
+    }
+
+    #[test]
+    fn text_which_reads_as_another_atom() {
+        for text in ["true", "false", "-1", "12", "nan", "inf", "-inf", "1.5", "127.0.0.1", "0x10"] {
+            let dumped = Atom::Text(text.to_string()).to_string();
+            assert!(dumped.starts_with('\\'), "{}", dumped);
+            assert_eq!(Atom::from_token(&dumped), Atom::Text(text.to_string()));
+        }
+    }
+
+    #[test]
+    fn string() {
+        assert_eq!(Atom::string("word"), Atom::Text("word".to_string()));
+        assert_eq!(Atom::string("two words"), Atom::Quoted("two words".to_string()));
+        assert_eq!(Atom::string("true"), Atom::Quoted("true".to_string()));
+        assert_eq!(Atom::string("12"), Atom::Quoted("12".to_string()));
+        assert_eq!(Atom::string("a=b"), Atom::Quoted("a=b".to_string()));
+        assert_eq!(Atom::string(""), Atom::Quoted("".to_string()));
+    }
+}
@@ -0,0 +1,298 @@
+use std::cmp::Ordering;
+use std::hash::{Hash, Hasher};
+use crate::atom::Radix;
+use crate::expression::{Atom, Expression, ExpressionData};
+use crate::expression::ExpressionData::{BindingExpr, List, Presence};
+use crate::lexical_span::LexicalSpan;
+
+const FNV_OFFSET_BASIS: u64 = 0xcbf29ce484222325;
+const FNV_PRIME: u64 = 0x100000001b3;
+
+const PRESENCE_TAG: u8 = 0;
+const BINDING_TAG: u8 = 1;
+const LIST_TAG: u8 = 2;
+const TEXT_TAG: u8 = 0;
+const NUMBER_TAG: u8 = 1;
+const INTEGER_TAG: u8 = 2;
+const FLOAT_TAG: u8 = 3;
+const BOOL_TAG: u8 = 4;
+const QUOTED_TAG: u8 = 5;
+
+/// 64 bit FNV-1a. Used instead of `DefaultHasher` because its output is specified, so canonical
+/// hashes can be persisted and compared between builds, platforms, and compiler versions.
+struct Fnv1a(u64);
+
+impl Fnv1a {
+    fn new() -> Self {
+        Self(FNV_OFFSET_BASIS)
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.0 ^= *byte as u64;
+            self.0 = self.0.wrapping_mul(FNV_PRIME);
+        }
+    }
+
+    fn write_tag(&mut self, tag: u8) {
+        self.write(&[tag]);
+    }
+
+    // Lengths are always written as little endian u64s so the encoding does not depend on the
+    // pointer width of the machine.
+    fn write_str(&mut self, s: &str) {
+        self.write(&(s.len() as u64).to_le_bytes());
+        self.write(s.as_bytes());
+    }
+
+    fn write_atom(&mut self, atom: &Atom) {
+        match atom {
+            Atom::Text(t) => {
+                self.write_tag(TEXT_TAG);
+                self.write_str(t);
+            }
+            Atom::Number(n) => {
+                self.write_tag(NUMBER_TAG);
+                self.write_str(n);
+            }
+            // The radix an integer was written in is presentation, not content.
+            Atom::Integer(i) => {
+                self.write_tag(INTEGER_TAG);
+                self.write(&[i.is_negative() as u8]);
+                self.write(&i.magnitude().to_le_bytes());
+            }
+            Atom::Float(f) => {
+                self.write_tag(FLOAT_TAG);
+                self.write(&f.value().to_bits().to_le_bytes());
+            }
+            Atom::Bool(b) => {
+                self.write_tag(BOOL_TAG);
+                self.write(&[*b as u8]);
+            }
+            Atom::Quoted(q) => {
+                self.write_tag(QUOTED_TAG);
+                self.write_str(q);
+            }
+        }
+    }
+
+    fn write_expression(&mut self, expression: &Expression) {
+        match &expression.data {
+            Presence(atom, _) => {
+                self.write_tag(PRESENCE_TAG);
+                self.write_atom(atom);
+            }
+            BindingExpr(binding) => {
+                self.write_tag(BINDING_TAG);
+                self.write_str(&binding.name);
+                self.write_expression(&binding.value);
+            }
+            List(elements, _) => {
+                self.write_tag(LIST_TAG);
+                self.write(&(elements.len() as u64).to_le_bytes());
+                for element in elements {
+                    self.write_expression(element);
+                }
+            }
+        }
+    }
+}
+
+impl Expression {
+    /// Compare two expressions by content alone, ignoring spans and comments. The trees must have
+    /// the same shape, with the same binding names and the same atoms, where integers written in
+    /// different radixes are equal. So `(x)` is not equal to `x`, and `x` is not equal to `"x"`,
+    /// even though they deserialize the same.
+    pub fn semantic_eq(&self, other: &Expression) -> bool {
+        self.semantic_cmp(other) == Ordering::Equal
+    }
+
+    /// Order two expressions by content alone, ignoring spans and comments.
+    pub fn semantic_cmp(&self, other: &Expression) -> Ordering {
+        match (&self.data, &other.data) {
+            (Presence(a, _), Presence(b, _)) => canonical_atom(a).cmp(&canonical_atom(b)),
+            (BindingExpr(a), BindingExpr(b)) => a.name
+                .cmp(&b.name)
+                .then_with(|| a.value.semantic_cmp(&b.value)),
+            (List(a, _), List(b, _)) => a.iter()
+                .zip(b)
+                .map(|(a, b)| a.semantic_cmp(b))
+                .find(|x| x.is_ne())
+                .unwrap_or(a.len().cmp(&b.len())),
+            (a, b) => kind_rank(a).cmp(&kind_rank(b))
+        }
+    }
+
+    /// A hash of the content of this expression, ignoring spans and comments. The hash is stable
+    /// across runs, platforms, and versions of Rust, so it can be used as a persistent cache key.
+    pub fn canonical_hash(&self) -> u64 {
+        let mut hasher = Fnv1a::new();
+        hasher.write_expression(self);
+        hasher.0
+    }
+
+    /// Convert this expression to its canonical form by removing all comments and zeroing all
+    /// spans. Two expressions are semantically equal exactly when their canonical forms are equal.
+    pub fn canonicalized(self) -> Self {
+        let data = match self.data {
+            Presence(atom, _) => Presence(canonical_atom(&atom), LexicalSpan::zeros()),
+            BindingExpr(mut binding) => {
+                binding.value = Box::new(binding.value.canonicalized());
+                binding.span = LexicalSpan::zeros();
+                BindingExpr(binding)
+            }
+            List(elements, _) => List(
+                elements.into_iter().map(|x| x.canonicalized()).collect(),
+                LexicalSpan::zeros()
+            ),
+        };
+
+        Expression::new(data, None)
+    }
+}
+
+fn canonical_atom(atom: &Atom) -> Atom {
+    match atom {
+        Atom::Integer(i) => Atom::Integer(i.with_radix(Radix::Decimal)),
+        other => other.clone(),
+    }
+}
+
+fn kind_rank(data: &ExpressionData) -> u8 {
+    match data {
+        Presence(_, _) => PRESENCE_TAG,
+        BindingExpr(_) => BINDING_TAG,
+        List(_, _) => LIST_TAG,
+    }
+}
+
+/// Wraps an `Expression` so that `Eq`, `Ord`, and `Hash` only consider its content. This makes
+/// it possible to key a `HashMap` or `BTreeMap` by configuration content, regardless of where the
+/// configuration came from or how it was commented.
+#[derive(Debug, Clone)]
+pub struct Canonical(pub Expression);
+
+impl Canonical {
+    pub fn into_inner(self) -> Expression {
+        self.0
+    }
+}
+
+impl From<Expression> for Canonical {
+    fn from(value: Expression) -> Self {
+        Self(value)
+    }
+}
+
+impl PartialEq for Canonical {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.semantic_eq(&other.0)
+    }
+}
+impl Eq for Canonical {}
+
+impl PartialOrd for Canonical {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+impl Ord for Canonical {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.semantic_cmp(&other.0)
+    }
+}
+
+impl Hash for Canonical {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        state.write_u64(self.0.canonical_hash())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use crate::parser::Parser;
+    use super::*;
+
+    fn built() -> Expression {
+        Expression::list(vec![
+            Expression::binding("name", Expression::presence("cat")),
+            Expression::binding("lives", Expression::presence(9)),
+            Expression::binding("toys", Expression::list(vec![
+                Expression::presence("ball"),
+                Expression::presence("string"),
+            ])),
+        ])
+    }
+
+    fn parsed(text: &str) -> Expression {
+        Parser::new(text).parse().unwrap()
+    }
+
+    #[test]
+    fn parsed_equals_built() {
+        let parsed = parsed("name = cat lives = 9 toys = (ball string)");
+        assert_ne!(parsed, built());
+        assert!(parsed.semantic_eq(&built()));
+    }
+
+    #[test]
+    fn comments_and_layout_are_ignored() {
+        let a = parsed("# the cat\nname = cat\nlives = 9\ntoys = (\n\tball\n\tstring\n)");
+        let b = parsed("name = cat lives = 9 toys = (ball string)");
+        assert!(a.semantic_eq(&b));
+        assert_eq!(a.canonical_hash(), b.canonical_hash());
+        assert_eq!(a.canonicalized(), b.canonicalized());
+    }
+
+    #[test]
+    fn different_content_is_not_equal() {
+        let a = parsed("name = cat lives = 9 toys = (ball string)");
+        let b = parsed("name = cat lives = 8 toys = (ball string)");
+        assert!(!a.semantic_eq(&b));
+        assert_ne!(a.canonical_hash(), b.canonical_hash());
+    }
+
+    #[test]
+    fn number_and_text_are_distinct() {
+        let text = Expression::presence(Atom::Text("9".to_string()));
+        let number = Expression::presence(9);
+        assert!(!text.semantic_eq(&number));
+        assert_ne!(text.canonical_hash(), number.canonical_hash());
+    }
+
+    #[test]
+    fn radix_is_ignored() {
+        let hex = parsed("0x10");
+        let decimal = parsed("16");
+        assert!(hex.semantic_eq(&decimal));
+        assert_eq!(hex.canonical_hash(), decimal.canonical_hash());
+        assert_eq!(hex.canonicalized(), decimal.canonicalized());
+    }
+
+    #[test]
+    fn nesting_is_part_of_the_hash() {
+        let flat = Expression::list(vec![Expression::presence("a"), Expression::presence("b")]);
+        let nested = Expression::list(vec![Expression::list(vec![Expression::presence("a")]), Expression::presence("b")]);
+        assert_ne!(flat.canonical_hash(), nested.canonical_hash());
+    }
+
+    #[test]
+    fn hash_is_stable() {
+        assert_eq!(Expression::presence("a").canonical_hash(), 0x351b187e6efd7517);
+    }
+
+    #[test]
+    fn canonicalized_strips_comments_and_spans() {
+        let canonical = parsed("# hello\nname = cat").canonicalized();
+        assert_eq!(canonical, Expression::list(vec![Expression::binding("name", Expression::presence("cat"))]));
+    }
+
+    #[test]
+    fn canonical_as_key() {
+        let mut cache = HashMap::new();
+        cache.insert(Canonical(built()), "artefact");
+        let key = Canonical(parsed("# comment\nname = cat lives = 9 toys = (ball string)"));
+        assert_eq!(cache.get(&key), Some(&"artefact"));
+    }
+}
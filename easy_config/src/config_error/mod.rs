@@ -5,15 +5,33 @@ use crate::config_error::describe::Describe;
 use crate::lexical_span::LexicalSpan;
 
 pub const ERROR_WINDOW_SIZE: usize = 10;
+
+// Spans are byte offsets, so the window around an error has to be widened to the nearest
+// character boundaries before slicing.
+fn floor_char_boundary(text: &str, mut index: usize) -> usize {
+    index = index.min(text.len());
+    while !text.is_char_boundary(index) {
+        index -= 1;
+    }
+    index
+}
+fn ceil_char_boundary(text: &str, mut index: usize) -> usize {
+    index = index.min(text.len());
+    while !text.is_char_boundary(index) {
+        index += 1;
+    }
+    index
+}
 fn build_error_area(span: LexicalSpan, source_text: impl AsRef<str>) -> String {
     let source_text = source_text.as_ref();
     
-    let lowest_bound = span.start().saturating_sub(ERROR_WINDOW_SIZE);
+    let start = floor_char_boundary(source_text, span.start());
+    let lowest_bound = floor_char_boundary(source_text, start.saturating_sub(ERROR_WINDOW_SIZE));
     let left_dots = if lowest_bound > 0 { "..." } else { "" };
 
-    let highest_bound = (span.end() + ERROR_WINDOW_SIZE).min(source_text.len());
+    let highest_bound = ceil_char_boundary(source_text, span.end() + ERROR_WINDOW_SIZE);
     let right_dots = if highest_bound < source_text.len() {"..."} else {""};
-    let index_of_offender = span.start() - lowest_bound;
+    let index_of_offender = source_text[lowest_bound..start].chars().count();
     let mut offset = " ".repeat(index_of_offender + left_dots.len());
     
     let span = LexicalSpan::new(lowest_bound, highest_bound);
@@ -70,7 +88,7 @@ impl<Kind: Describe> Display for ConfigError<Kind> {
         write!(f, "{}", match self {
             ConfigError::FirstLevelError(kind, area) => get_description(kind, area),
             ConfigError::ContextualizedError(context, err) =>
-                format!("{}\n{}", context, err.to_string())
+                format!("{}\n{}", context, err)
         })
     }
 }
@@ -84,4 +102,31 @@ impl<T, Kind> Contextualize for Result<T, ConfigError<Kind>> {
     }
 }
 
-impl<T: Debug+Describe> std::error::Error for ConfigError<T> {}
\ No newline at end of file
+impl<T: Debug+Describe> std::error::Error for ConfigError<T> {}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug)]
+    struct Oops;
+    impl Describe for Oops {
+        fn describe(&self) -> String {
+            "oops".to_string()
+        }
+    }
+
+    #[test]
+    fn position_is_one_based() {
+        let error = ConfigError::on_span(Oops, LexicalSpan::new(8, 9), "a = b\nc = d");
+        assert!(error.to_string().starts_with("Error at 2:3\n"), "{}", error);
+    }
+
+    #[test]
+    fn window_is_cut_on_character_boundaries() {
+        // Ten bytes back from the `x` lands in the middle of an `é`, so the window starts just before it.
+        let text = "éééééé = x";
+        let area = build_error_area(LexicalSpan::new(15, 16), text);
+        assert_eq!(area, "...éééé = x\n          ^");
+        assert!(ConfigError::on_span(Oops, LexicalSpan::new(15, 16), text).to_string().starts_with("Error at 1:10\n"));
+    }
+}
@@ -0,0 +1,156 @@
+//! Conversions between `Expression` and `serde_json::Value`.
+
+use serde_json::{Number, Value as Json};
+use crate::atom::Integer;
+use crate::expression::Expression;
+use crate::serialization::serialization_error::{Kind, SerializationError};
+use super::Target;
+
+impl Target for Json {
+    const FORMAT: &'static str = "JSON";
+
+    fn map(entries: Vec<(String, Self)>) -> Self {
+        Json::Object(entries.into_iter().collect())
+    }
+    fn list(elements: Vec<Self>) -> Self {
+        Json::Array(elements)
+    }
+    fn string(text: String) -> Self {
+        Json::String(text)
+    }
+    fn integer(integer: &Integer) -> Option<Self> {
+        integer.convert::<u64>().map(Number::from)
+            .or_else(|| integer.convert::<i64>().map(Number::from))
+            .map(Json::Number)
+    }
+    fn float(value: f64) -> Option<Self> {
+        Number::from_f64(value).map(Json::Number)
+    }
+    fn bool(value: bool) -> Self {
+        Json::Bool(value)
+    }
+    fn null() -> Option<Self> {
+        Some(Json::Null)
+    }
+}
+
+/// Convert a JSON value into an `Expression`. Objects become lists of bindings, and `null`
+/// becomes `None`, so it reads as an empty `Option`.
+pub fn from_json(json: &Json) -> Result<Expression, SerializationError> {
+    Ok(match json {
+        Json::Null => super::none(),
+        Json::Bool(b) => Expression::presence(*b),
+        Json::Number(n) => match (n.as_u64(), n.as_i64()) {
+            (Some(u), _) => Expression::presence(u),
+            (_, Some(i)) => Expression::presence(i),
+            _ => Expression::presence(n.as_f64().expect("a JSON number is a u64, an i64 or an f64")),
+        },
+        Json::String(s) => super::string(s.as_str()),
+        Json::Array(elements) => Expression::list(Result::from_iter(elements.iter().map(from_json))?),
+        Json::Object(object) => super::map(object.iter().map(|(k, v)| (k.as_str(), from_json(v))))?,
+    })
+}
+
+/// Convert an `Expression` parsed from `source_text` into a JSON value. A bare `None` becomes
+/// `null`. Fails on integers or floats JSON can't hold, such as `NaN`.
+pub fn to_json(expression: Expression, source_text: impl AsRef<str>) -> Result<Json, SerializationError> {
+    super::export(expression, source_text.as_ref())
+}
+
+/// Rewrite a JSON document as a formatted easy_config file.
+pub fn from_json_str(json_text: impl AsRef<str>) -> Result<String, SerializationError> {
+    let json: Json = serde_json::from_str(json_text.as_ref())
+        .map_err(|e| SerializationError::FirstLevelError(Kind::Custom(e.to_string()), String::new()))?;
+    Ok(super::to_document(&from_json(&json)?))
+}
+
+/// Rewrite an easy_config file as pretty printed JSON.
+pub fn to_json_string(source_text: impl AsRef<str>) -> Result<String, SerializationError> {
+    let source_text = source_text.as_ref();
+    let json = to_json(super::parse(source_text)?, source_text)?;
+    Ok(serde_json::to_string_pretty(&json).expect("a JSON value can always be written"))
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+    use crate::convert::to_document;
+    use crate::parser::Parser;
+    use super::*;
+
+    fn parse(source: &str) -> Expression {
+        Parser::new(source).parse().unwrap().get_first_expr_or_panic()
+    }
+
+    #[test]
+    fn scalars() {
+        assert_eq!(to_json(parse("cat"), "cat").unwrap(), json!("cat"));
+        assert_eq!(to_json(parse("-3"), "-3").unwrap(), json!(-3));
+        assert_eq!(to_json(parse("0x10"), "0x10").unwrap(), json!(16));
+        assert_eq!(to_json(parse("2.5"), "2.5").unwrap(), json!(2.5));
+        assert_eq!(to_json(parse("\"two words\""), "\"two words\"").unwrap(), json!("two words"));
+    }
+
+    #[test]
+    fn maps_keep_their_order() {
+        let source = "(zebra = 1 apple = (x y) verbose)";
+        let json = to_json(parse(source), source).unwrap();
+        assert_eq!(json.to_string(), r#"{"zebra":1,"apple":["x","y"],"verbose":true}"#);
+    }
+
+    #[test]
+    fn round_trip() {
+        let json = json!({
+            "name": "my service",
+            "port": 8080,
+            "ratio": 0.25,
+            "enabled": false,
+            "tags": ["a"],
+            "nested": [{"depth": -2}, "true", "12"],
+        });
+        let text = from_json_str(json.to_string()).unwrap();
+        assert_eq!(serde_json::from_str::<Json>(&to_json_string(&text).unwrap()).unwrap(), json);
+    }
+
+    #[test]
+    fn document_layout() {
+        let text = from_json_str(r#"{"a": 1, "b": [1, 2]}"#).unwrap();
+        assert_eq!(text, "a = 1\nb = (1 2)\n");
+    }
+
+    #[test]
+    fn null_is_none() {
+        assert_eq!(from_json(&json!({"x": null})).unwrap(), Expression::list(vec![
+            Expression::binding("x", Expression::presence("None"))
+        ]));
+    }
+
+    #[test]
+    fn null_and_the_string_none_stay_distinct() {
+        let json = json!({"x": null, "y": "None"});
+        let document = to_document(&from_json(&json).unwrap());
+        assert_eq!(document, "x = None\ny = \"None\"\n");
+        assert_eq!(serde_json::from_str::<Json>(&to_json_string(&document).unwrap()).unwrap(), json);
+    }
+
+    #[test]
+    fn quoted_keys() {
+        let json = json!({"two words": 1, "a.b": 2});
+        let document = to_document(&from_json(&json).unwrap());
+        assert_eq!(document, "\"two words\" = 1\n\"a.b\" = 2\n");
+        assert_eq!(serde_json::from_str::<Json>(&to_json_string(&document).unwrap()).unwrap(), json);
+    }
+
+    #[test]
+    fn too_large() {
+        let source = "340282366920938463463374607431768211455";
+        let error = to_json(parse(source), source).unwrap_err();
+        assert!(matches!(error.kind(), Kind::Unrepresentable { format: "JSON", .. }));
+    }
+
+    #[test]
+    fn duplicate_keys() {
+        let error = to_json_string("a = 1 a = 2").unwrap_err();
+        assert!(matches!(error.kind(), Kind::DuplicateKey(k) if k == "a"));
+    }
+}
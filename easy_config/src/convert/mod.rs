@@ -0,0 +1,142 @@
+//! Conversions between easy_config and other configuration formats, each behind a feature of the
+//! same name: `json`, `toml` and `yaml`.
+//!
+//! Lists containing bindings are read as maps, the same way `Value` reads them, and the order of
+//! entries is kept in both directions. Nothing is lost converting into easy_config, except that
+//! empty maps become empty lists and TOML datetimes become strings. JSON and YAML nulls become
+//! `None` and back, while a string spelled `None` is quoted so it stays a string. Comments are
+//! lost converting out of easy_config, since the other formats' value types can't hold them.
+
+#[cfg(feature = "json")]
+pub mod json;
+#[cfg(feature = "toml")]
+pub mod toml;
+#[cfg(feature = "yaml")]
+pub mod yaml;
+
+use itertools::Itertools;
+use crate::atom::{Atom, Integer};
+use crate::config_error::Contextualize;
+use crate::expression::{Expression, ExpressionData};
+use crate::lexical_span::LexicalSpan;
+use crate::parser::Parser;
+use crate::serialization::serialization_error::{Kind, SerializationError};
+
+/// What `Option::None` is written as, and so what nulls are converted into.
+const NONE: &str = "None";
+
+/// A value of another format, which can be built from an `Expression`.
+pub(crate) trait Target: Sized {
+    const FORMAT: &'static str;
+
+    fn map(entries: Vec<(String, Self)>) -> Self;
+    fn list(elements: Vec<Self>) -> Self;
+    fn string(text: String) -> Self;
+    fn integer(integer: &Integer) -> Option<Self>;
+    fn float(value: f64) -> Option<Self>;
+    fn bool(value: bool) -> Self;
+    /// The format's null, if it has one.
+    fn null() -> Option<Self>;
+}
+
+fn unrepresentable(value: impl ToString, format: &'static str, span: LexicalSpan, source_text: &str) -> SerializationError {
+    SerializationError::on_span(Kind::Unrepresentable { value: value.to_string(), format }, span, source_text)
+}
+
+pub(crate) fn export<T: Target>(expression: Expression, source_text: &str) -> Result<T, SerializationError> {
+    match expression.data {
+        ExpressionData::Presence(atom, span) => export_atom(atom, span, source_text),
+        ExpressionData::BindingExpr(_) => export_map(vec![expression], source_text),
+        ExpressionData::List(elements, _) if elements.iter().any(|x| x.is_binding()) => export_map(elements, source_text),
+        ExpressionData::List(elements, _) => Ok(T::list(Result::from_iter(
+            elements.into_iter().map(|x| export(x, source_text))
+        )?)),
+    }
+}
+
+fn export_atom<T: Target>(atom: Atom, span: LexicalSpan, source_text: &str) -> Result<T, SerializationError> {
+    match atom {
+        Atom::Text(s) if s == NONE => Ok(T::null().unwrap_or_else(|| T::string(s))),
+        Atom::Text(s) | Atom::Number(s) | Atom::Quoted(s) => Ok(T::string(s)),
+        Atom::Integer(i) => T::integer(&i).ok_or_else(|| unrepresentable(i, T::FORMAT, span, source_text)),
+        Atom::Float(f) => T::float(f.value()).ok_or_else(|| unrepresentable(f.literal(), T::FORMAT, span, source_text)),
+        Atom::Bool(b) => Ok(T::bool(b)),
+    }
+}
+
+// Mirrors `Value`: bare words are flags, which other formats hold as `true`.
+fn export_map<T: Target>(elements: Vec<Expression>, source_text: &str) -> Result<T, SerializationError> {
+    let mut entries: Vec<(String, T)> = Vec::with_capacity(elements.len());
+    for element in elements {
+        let span = element.span();
+        let (key, value) = match element.data {
+            ExpressionData::BindingExpr(binding) => {
+                let value = export(*binding.value, source_text)
+                    .contextualize(format!("Error while converting the value of {}", binding.name))?;
+                (binding.name, value)
+            }
+            ExpressionData::Presence(Atom::Text(flag), _) => (flag, T::bool(true)),
+            _ => return Err(SerializationError::on_span(Kind::ExpectedBinding(element), span, source_text))
+                .contextualize("Only bindings and flags may appear in a map"),
+        };
+
+        if entries.iter().any(|(k, _)| *k == key) {
+            return Err(SerializationError::on_span(Kind::DuplicateKey(key), span, source_text))
+        }
+        entries.push((key, value));
+    }
+
+    Ok(T::map(entries))
+}
+
+// A string spelled like `None` is quoted, so it isn't read back as a null.
+pub(crate) fn string(text: impl Into<String>) -> Expression {
+    let text = text.into();
+    let atom = if text == NONE { Atom::Quoted(text) } else { Atom::string(text) };
+    Expression::presence(atom)
+}
+
+#[cfg(any(feature = "json", feature = "yaml"))]
+pub(crate) fn none() -> Expression {
+    Expression::presence(NONE)
+}
+
+/// Build a map from the entries of another format. Keys which aren't a single word are quoted
+/// when the map is written.
+pub(crate) fn map<'a>(entries: impl IntoIterator<Item = (&'a str, Result<Expression, SerializationError>)>) -> Result<Expression, SerializationError> {
+    let mut bindings = Vec::new();
+    for (key, value) in entries {
+        let value = value.contextualize(format!("Error while converting the value of {}", key))?;
+        bindings.push(Expression::binding(key, value));
+    }
+
+    Ok(Expression::list(bindings))
+}
+
+/// Parse `source_text` as a whole file, ready to be converted to another format. Like `Value`, a
+/// file holding a single element which isn't a binding stands for that element.
+pub(crate) fn parse(source_text: &str) -> Result<Expression, SerializationError> {
+    let finished_parser = Parser::new(source_text).parse();
+    if !finished_parser.errors().is_empty() {
+        return Err(SerializationError::FirstLevelError(Kind::ParserErrors(finished_parser.errors().clone()), String::new()));
+    }
+
+    let file = finished_parser.unwrap();
+    match file.data {
+        ExpressionData::List(mut elements, _) if elements.len() == 1 && !elements[0].is_binding() => Ok(elements.remove(0)),
+        data => Ok(Expression::new(data, None)),
+    }
+}
+
+/// Lay `expression` out as a formatted file. A map is written as bindings at the top level of
+/// the file, rather than wrapped in a list, which reads back as the same map.
+pub fn to_document(expression: &Expression) -> String {
+    let text = match &expression.data {
+        ExpressionData::List(elements, _) if !elements.is_empty() && elements.iter().all(|x| x.is_binding()) => {
+            elements.iter().map(|x| x.dump()).join("\n")
+        }
+        _ => expression.dump(),
+    };
+
+    crate::fmt::format(&text).expect("dumped expressions always parse")
+}
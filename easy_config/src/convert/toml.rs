@@ -0,0 +1,130 @@
+//! Conversions between `Expression` and `toml::Value`, and migrating `.toml` files to easy_config
+//! and back.
+
+use toml::{Table, Value as Toml};
+use crate::atom::Integer;
+use crate::config_error::Contextualize;
+use crate::expression::Expression;
+use crate::serialization::serialization_error::{Kind, SerializationError};
+use super::Target;
+
+impl Target for Toml {
+    const FORMAT: &'static str = "TOML";
+
+    fn map(entries: Vec<(String, Self)>) -> Self {
+        Toml::Table(entries.into_iter().collect())
+    }
+    fn list(elements: Vec<Self>) -> Self {
+        Toml::Array(elements)
+    }
+    fn string(text: String) -> Self {
+        Toml::String(text)
+    }
+    fn integer(integer: &Integer) -> Option<Self> {
+        integer.convert::<i64>().map(Toml::Integer)
+    }
+    fn float(value: f64) -> Option<Self> {
+        Some(Toml::Float(value))
+    }
+    fn bool(value: bool) -> Self {
+        Toml::Boolean(value)
+    }
+    fn null() -> Option<Self> {
+        None
+    }
+}
+
+/// Convert a TOML value into an `Expression`. Tables become lists of bindings, and datetimes
+/// become strings.
+pub fn from_toml(toml: &Toml) -> Result<Expression, SerializationError> {
+    Ok(match toml {
+        Toml::String(s) => super::string(s.as_str()),
+        Toml::Integer(i) => Expression::presence(*i),
+        Toml::Float(f) => Expression::presence(*f),
+        Toml::Boolean(b) => Expression::presence(*b),
+        Toml::Datetime(d) => super::string(d.to_string()),
+        Toml::Array(elements) => Expression::list(Result::from_iter(elements.iter().map(from_toml))?),
+        Toml::Table(table) => super::map(table.iter().map(|(k, v)| (k.as_str(), from_toml(v))))?,
+    })
+}
+
+/// Convert an `Expression` parsed from `source_text` into a TOML value. Fails on integers which
+/// don't fit in an `i64`.
+pub fn to_toml(expression: Expression, source_text: impl AsRef<str>) -> Result<Toml, SerializationError> {
+    super::export(expression, source_text.as_ref())
+}
+
+/// Migrate a `.toml` file to an equivalent, formatted easy_config file.
+pub fn from_toml_str(toml_text: impl AsRef<str>) -> Result<String, SerializationError> {
+    let table: Table = toml_text.as_ref().parse()
+        .map_err(|e: toml::de::Error| SerializationError::FirstLevelError(Kind::Custom(e.to_string()), String::new()))
+        .contextualize("Error while parsing TOML")?;
+    Ok(super::to_document(&from_toml(&Toml::Table(table))?))
+}
+
+/// Migrate an easy_config file back to TOML. The file has to be a map, since a TOML document is
+/// always a table.
+pub fn to_toml_string(source_text: impl AsRef<str>) -> Result<String, SerializationError> {
+    let source_text = source_text.as_ref();
+    match to_toml(super::parse(source_text)?, source_text)? {
+        Toml::Table(table) => toml::to_string(&table)
+            .map_err(|e| SerializationError::FirstLevelError(Kind::Custom(e.to_string()), String::new())),
+        other => Err(SerializationError::FirstLevelError(
+            Kind::Unrepresentable { value: format!("A {}", other.type_str()), format: "the root of a TOML document" },
+            String::new()
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CARGO: &str = r#"[package]
+name = "easy_config"
+version = "0.4.4"
+keywords = ["config"]
+
+[dependencies]
+itertools = "0.14.0"
+serde = { version = "1", optional = true }
+
+[features]
+serde = ["dep:serde"]
+"#;
+
+    #[test]
+    fn migrate() {
+        let text = from_toml_str(CARGO).unwrap();
+        assert_eq!(text, concat!(
+            "package = (name = easy_config version = \"0.4.4\" keywords = (config))\n",
+            "dependencies = (itertools = \"0.14.0\" serde = (version = \"1\" optional = true))\n",
+            "features = (serde = (dep:serde))\n",
+        ));
+    }
+
+    #[test]
+    fn migrate_and_back() {
+        let text = from_toml_str(CARGO).unwrap();
+        let back: Table = to_toml_string(&text).unwrap().parse().unwrap();
+        assert_eq!(back, CARGO.parse::<Table>().unwrap());
+    }
+
+    #[test]
+    fn datetimes_become_strings() {
+        let text = from_toml_str("released = 1979-05-27T07:32:00Z").unwrap();
+        assert_eq!(text, "released = \"1979-05-27T07:32:00Z\"\n");
+    }
+
+    #[test]
+    fn root_must_be_a_table() {
+        let error = to_toml_string("(1 2 3)").unwrap_err();
+        assert!(matches!(error.kind(), Kind::Unrepresentable { .. }));
+    }
+
+    #[test]
+    fn invalid_toml() {
+        let error = from_toml_str("a = ").unwrap_err();
+        assert!(matches!(error.kind(), Kind::Custom(_)));
+    }
+}
@@ -0,0 +1,123 @@
+//! Conversions between `Expression` and `serde_yaml::Value`.
+
+use serde_yaml::{Number, Value as Yaml};
+use crate::atom::Integer;
+use crate::config_error::Contextualize;
+use crate::expression::Expression;
+use crate::serialization::serialization_error::{Kind, SerializationError};
+use super::Target;
+
+impl Target for Yaml {
+    const FORMAT: &'static str = "YAML";
+
+    fn map(entries: Vec<(String, Self)>) -> Self {
+        Yaml::Mapping(entries.into_iter().map(|(k, v)| (Yaml::String(k), v)).collect())
+    }
+    fn list(elements: Vec<Self>) -> Self {
+        Yaml::Sequence(elements)
+    }
+    fn string(text: String) -> Self {
+        Yaml::String(text)
+    }
+    fn integer(integer: &Integer) -> Option<Self> {
+        integer.convert::<u64>().map(Number::from)
+            .or_else(|| integer.convert::<i64>().map(Number::from))
+            .map(Yaml::Number)
+    }
+    fn float(value: f64) -> Option<Self> {
+        Some(Yaml::Number(Number::from(value)))
+    }
+    fn bool(value: bool) -> Self {
+        Yaml::Bool(value)
+    }
+    fn null() -> Option<Self> {
+        Some(Yaml::Null)
+    }
+}
+
+/// Convert a YAML value into an `Expression`. Mappings become lists of bindings, so their keys
+/// have to be strings. `null` becomes `None`, and tags are dropped.
+pub fn from_yaml(yaml: &Yaml) -> Result<Expression, SerializationError> {
+    Ok(match yaml {
+        Yaml::Null => super::none(),
+        Yaml::Bool(b) => Expression::presence(*b),
+        Yaml::Number(n) => match (n.as_u64(), n.as_i64()) {
+            (Some(u), _) => Expression::presence(u),
+            (_, Some(i)) => Expression::presence(i),
+            _ => Expression::presence(n.as_f64().expect("a YAML number is a u64, an i64 or an f64")),
+        },
+        Yaml::String(s) => super::string(s.as_str()),
+        Yaml::Sequence(elements) => Expression::list(Result::from_iter(elements.iter().map(from_yaml))?),
+        Yaml::Mapping(mapping) => {
+            let mut entries = Vec::with_capacity(mapping.len());
+            for (key, value) in mapping {
+                let Some(key) = key.as_str() else {
+                    return Err(SerializationError::FirstLevelError(
+                        Kind::Unrepresentable { value: format!("The key {:?}", key), format: "easy_config" },
+                        String::new()
+                    )).contextualize("Only strings may be used as keys")
+                };
+                entries.push((key, from_yaml(value)));
+            }
+            super::map(entries)?
+        }
+        Yaml::Tagged(tagged) => from_yaml(&tagged.value)?,
+    })
+}
+
+/// Convert an `Expression` parsed from `source_text` into a YAML value. A bare `None` becomes
+/// `null`.
+pub fn to_yaml(expression: Expression, source_text: impl AsRef<str>) -> Result<Yaml, SerializationError> {
+    super::export(expression, source_text.as_ref())
+}
+
+/// Rewrite a YAML document as a formatted easy_config file.
+pub fn from_yaml_str(yaml_text: impl AsRef<str>) -> Result<String, SerializationError> {
+    let yaml: Yaml = serde_yaml::from_str(yaml_text.as_ref())
+        .map_err(|e| SerializationError::FirstLevelError(Kind::Custom(e.to_string()), String::new()))
+        .contextualize("Error while parsing YAML")?;
+    Ok(super::to_document(&from_yaml(&yaml)?))
+}
+
+/// Rewrite an easy_config file as YAML.
+pub fn to_yaml_string(source_text: impl AsRef<str>) -> Result<String, SerializationError> {
+    let source_text = source_text.as_ref();
+    let yaml = to_yaml(super::parse(source_text)?, source_text)?;
+    serde_yaml::to_string(&yaml)
+        .map_err(|e| SerializationError::FirstLevelError(Kind::Custom(e.to_string()), String::new()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const COMPOSE: &str = "services:
+  web:
+    image: nginx
+    ports:
+    - 80
+    - 443
+    healthy: true
+  db:
+    image: postgres
+    password: null
+";
+
+    #[test]
+    fn migrate() {
+        let text = from_yaml_str(COMPOSE).unwrap();
+        assert_eq!(text, "services = (\n\tweb = (image = nginx ports = (80 443) healthy = true)\n\tdb = (image = postgres password = None)\n)\n");
+    }
+
+    #[test]
+    fn migrate_and_back() {
+        let text = from_yaml_str("name: my service\nlimits: [1, -2, 0.5]\n").unwrap();
+        assert_eq!(to_yaml_string(&text).unwrap(), "name: my service\nlimits:\n- 1\n- -2\n- 0.5\n");
+    }
+
+    #[test]
+    fn non_string_keys() {
+        let error = from_yaml_str("1: a").unwrap_err();
+        assert!(matches!(error.kind(), Kind::Unrepresentable { .. }));
+    }
+}
@@ -0,0 +1,312 @@
+//! Exporting configs as environment variables, and reading them back.
+//!
+//! Bindings are flattened into one variable each, named by joining the path to the binding with
+//! `__` after a prefix, so `server = (port = 8080)` with the prefix `APP` becomes
+//! `APP_SERVER__PORT=8080`. Anything which isn't a map, such as a list or an enum, is written as
+//! easy_config text in a single variable.
+
+use std::io::Write;
+use itertools::Itertools;
+use crate::atom::{quote, Atom};
+use crate::expression::{Expression, ExpressionData};
+use crate::lexer::Lexer;
+use crate::lexer::token::Kind as TokenKind;
+use crate::parser::Parser;
+use crate::serialization::EasyConfig;
+use crate::serialization::serialization_error::{Kind, SerializationError};
+
+const SEPARATOR: &str = "__";
+
+// Whether `expr` is the name a derived struct writes before its fields. Struct names are
+// UpperCamelCase while field names are snake_case, which tells them apart from a map's flags.
+fn is_struct_name(expr: &Expression) -> bool {
+    matches!(&expr.data, ExpressionData::Presence(Atom::Text(name), _) if name.starts_with(|c: char| c.is_ascii_uppercase()))
+}
+
+fn is_flag(expr: &Expression) -> bool {
+    matches!(&expr.data, ExpressionData::Presence(Atom::Text(_) | Atom::Quoted(_), _))
+}
+
+// The bindings and flags of a list, if it is a map. Structs are written with their name first,
+// which isn't part of the map.
+fn map_entries(expr: &Expression) -> Option<Vec<&Expression>> {
+    let ExpressionData::List(elements, _) = &expr.data else {
+        return None
+    };
+
+    let skip = usize::from(elements.len() > 1 && is_struct_name(&elements[0]));
+    let entries = elements[skip..].iter().collect_vec();
+    let is_map = entries.iter().any(|x| x.is_binding()) && entries.iter().all(|x| x.is_binding() || is_flag(x));
+    is_map.then_some(entries)
+}
+
+fn leaf_text(expr: &Expression) -> String {
+    match &expr.data {
+        ExpressionData::Presence(atom, _) => atom.to_text(),
+        _ => expr.uncomented_dump(),
+    }
+}
+
+fn key(prefix: &str, path: &[String]) -> String {
+    match (prefix, path.is_empty()) {
+        (prefix, true) => prefix.to_uppercase(),
+        ("", false) => path.join(SEPARATOR),
+        (prefix, false) => format!("{}_{}", prefix.to_uppercase(), path.join(SEPARATOR)),
+    }
+}
+
+fn flatten_into(expr: &Expression, prefix: &str, path: &mut Vec<String>, pairs: &mut Vec<(String, String)>) {
+    let Some(entries) = map_entries(expr) else {
+        pairs.push((key(prefix, path), leaf_text(expr)));
+        return
+    };
+
+    for entry in entries {
+        match &entry.data {
+            ExpressionData::BindingExpr(binding) => {
+                path.push(binding.name.to_uppercase());
+                flatten_into(&binding.value, prefix, path, pairs);
+            }
+            // A flag is only ever set, so it is written the way a set bool would be.
+            ExpressionData::Presence(Atom::Text(flag) | Atom::Quoted(flag), _) => {
+                path.push(flag.to_uppercase());
+                pairs.push((key(prefix, path), "true".to_string()));
+            }
+            _ => unreachable!("map entries are bindings or flags"),
+        }
+        path.pop();
+    }
+}
+
+/// Flatten `value` into `KEY=value` pairs, in the order its fields are serialized. Keys start
+/// with `prefix` followed by `_`, unless `prefix` is empty.
+pub fn flatten(value: &impl EasyConfig, prefix: impl AsRef<str>) -> Vec<(String, String)> {
+    let mut pairs = Vec::new();
+    flatten_into(&value.serialize(), prefix.as_ref(), &mut Vec::new(), &mut pairs);
+    pairs
+}
+
+// Values are only quoted when a shell would otherwise split or expand them. Nothing is expanded
+// inside single quotes, and a single quote itself is written by closing the quotes, escaping it,
+// and opening them again.
+fn dotenv_value(value: &str) -> String {
+    let needs_quotes = value.is_empty() || value.contains(|c: char| {
+        c.is_whitespace() || c.is_control() || !(c.is_alphanumeric() || "_-+.,:/@%^=".contains(c))
+    });
+
+    if needs_quotes { format!("'{}'", value.replace('\'', r"'\''")) } else { value.to_string() }
+}
+
+/// Write `value` into `writer` as a `.env` file, one flattened variable per line. Values are
+/// single quoted where needed, so a shell sourcing the file reads them literally.
+pub fn write_dotenv(value: &impl EasyConfig, prefix: impl AsRef<str>, writer: &mut impl Write) -> std::io::Result<()> {
+    for (key, value) in flatten(value, prefix) {
+        writeln!(writer, "{}={}", key, dotenv_value(&value))?;
+    }
+    Ok(())
+}
+
+/// Flatten `value` into the text of a `.env` file.
+pub fn to_dotenv(value: &impl EasyConfig, prefix: impl AsRef<str>) -> String {
+    let mut out = Vec::new();
+    write_dotenv(value, prefix, &mut out).expect("writing to a Vec can not fail");
+    String::from_utf8(out).expect("to_dotenv only writes UTF-8")
+}
+
+/// A variable's path, and what the path leads to.
+#[derive(Default)]
+struct Node {
+    value: Option<String>,
+    children: Vec<(String, Node)>,
+}
+impl Node {
+    fn child(&mut self, name: &str) -> &mut Node {
+        let index = match self.children.iter().position(|(n, _)| n == name) {
+            Some(index) => index,
+            None => {
+                self.children.push((name.to_string(), Node::default()));
+                self.children.len() - 1
+            }
+        };
+        &mut self.children[index].1
+    }
+
+    // Each binding goes on its own line, so nothing a value holds can run into the next one.
+    fn write(&self, out: &mut String) {
+        for (name, node) in &self.children {
+            out.push_str(name);
+            out.push_str(" = ");
+            match &node.value {
+                Some(value) => out.push_str(value),
+                None => {
+                    out.push_str("(\n");
+                    node.write(out);
+                    out.push(')');
+                }
+            }
+            out.push('\n');
+        }
+    }
+}
+
+// Values which are already a single easy_config expression are kept as written, so lists and
+// numbers keep their meaning. Anything else is read as a string, including values with a
+// comment, since the comment would hide the rest of the value.
+fn embedded_value(value: &str) -> String {
+    let has_comment = Lexer::lossless(value).any(|x| x.kind() == TokenKind::Comment);
+    let finished = Parser::new(value).parse();
+    if !has_comment && finished.errors().is_empty() {
+        if let ExpressionData::List(elements, _) = &finished.unwrap().data {
+            if elements.len() == 1 && !elements[0].is_binding() {
+                return value.trim().to_string()
+            }
+        }
+    }
+
+    quote(value)
+}
+
+fn conflict(key: String) -> SerializationError {
+    SerializationError::FirstLevelError(Kind::DuplicateKey(key), String::new())
+        .contextualize("A variable can't hold a value and also be the prefix of other variables")
+}
+
+/// Rebuild the expressions `flatten` was given from `(key, value)` pairs. Pairs whose key doesn't
+/// start with `prefix` and `_`, or whose path isn't made of single words, are skipped. Returns
+/// the expressions along with the source text they were parsed from, which should be passed to
+/// `EasyConfig::deserialize`.
+pub fn from_vars(prefix: impl AsRef<str>, vars: impl IntoIterator<Item = (String, String)>) -> Result<(Expression, String), SerializationError> {
+    let prefix = match prefix.as_ref() {
+        "" => String::new(),
+        prefix => format!("{}_", prefix.to_uppercase()),
+    };
+
+    let mut root = Node::default();
+    for (key, value) in vars.into_iter().sorted() {
+        let Some(path) = key.strip_prefix(&prefix) else { continue };
+        let path = path.split(SEPARATOR).map(|x| x.to_lowercase()).collect_vec();
+        if path.iter().any(|x| !Atom::string(x.as_str()).is_text()) {
+            continue
+        }
+
+        let mut node = &mut root;
+        for name in &path {
+            if node.value.is_some() {
+                return Err(conflict(key))
+            }
+            node = node.child(name);
+        }
+        if node.value.is_some() || !node.children.is_empty() {
+            return Err(conflict(key))
+        }
+        node.value = Some(embedded_value(&value));
+    }
+
+    let mut source_text = String::new();
+    root.write(&mut source_text);
+    let finished = Parser::new(&source_text).parse();
+    if !finished.errors().is_empty() {
+        return Err(SerializationError::FirstLevelError(Kind::ParserErrors(finished.errors().clone()), String::new()))
+    }
+    Ok((finished.unwrap(), source_text))
+}
+
+/// Rebuild the expressions `flatten` was given from the variables of this process.
+pub fn from_env(prefix: impl AsRef<str>) -> Result<(Expression, String), SerializationError> {
+    from_vars(prefix, std::env::vars_os().filter_map(|(k, v)| Some((k.into_string().ok()?, v.into_string().ok()?))))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::serialization::tests::parse;
+    use crate::value::Value;
+    use super::*;
+
+    fn pairs(items: &[(&str, &str)]) -> Vec<(String, String)> {
+        items.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect()
+    }
+
+    fn config() -> Value {
+        parse("name = \"my app\" server = (port = 8080 hosts = (a b)) debug = false").unwrap()
+    }
+
+    #[test]
+    fn flattening() {
+        assert_eq!(flatten(&config(), "app"), pairs(&[
+            ("APP_DEBUG", "false"),
+            ("APP_NAME", "my app"),
+            ("APP_SERVER__HOSTS", "(a b)"),
+            ("APP_SERVER__PORT", "8080"),
+        ]));
+        assert_eq!(flatten(&config(), "")[0].0, "DEBUG");
+    }
+
+    #[test]
+    fn dotenv() {
+        assert_eq!(to_dotenv(&config(), "APP"), concat!(
+            "APP_DEBUG=false\n",
+            "APP_NAME='my app'\n",
+            "APP_SERVER__HOSTS='(a b)'\n",
+            "APP_SERVER__PORT=8080\n",
+        ));
+    }
+
+    #[test]
+    fn flags_are_set() {
+        let map = Value::Map([("a".to_string(), Value::Flag), ("b".to_string(), Value::Bool(true))].into());
+        assert_eq!(flatten(&map, "APP"), pairs(&[("APP_A", "true"), ("APP_B", "true")]));
+    }
+
+    #[test]
+    fn dotenv_quoting() {
+        assert_eq!(dotenv_value("localhost:8080"), "localhost:8080");
+        assert_eq!(dotenv_value(""), "''");
+        assert_eq!(dotenv_value("my app"), "'my app'");
+        assert_eq!(dotenv_value("$HOME"), "'$HOME'");
+        assert_eq!(dotenv_value("it's"), r"'it'\''s'");
+        assert_eq!(dotenv_value("a\nb"), "'a\nb'");
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn shells_read_values_literally() {
+        let values = ["$HOME", "`id`", "$(id)", "it's", "'\\''", "a \"b\" \\n", "~", "tab\tand\nnewline", "*", ""];
+        let map = Value::Map(values.iter().enumerate().map(|(i, v)| (format!("v{}", i), Value::String(v.to_string()))).collect());
+        let path = std::env::temp_dir().join(format!("easy_config_env_test_{}.env", std::process::id()));
+        std::fs::write(&path, to_dotenv(&map, "APP")).unwrap();
+
+        for (i, value) in values.iter().enumerate() {
+            let script = format!(". '{}'; printf %s \"$APP_V{}\"", path.display(), i);
+            let output = std::process::Command::new("sh").args(["-c", &script]).output().unwrap();
+            assert_eq!(String::from_utf8(output.stdout).unwrap(), *value);
+        }
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn round_trip() {
+        let mut vars = flatten(&config(), "APP");
+        vars.push(("PATH".to_string(), "/usr/bin".to_string()));
+        vars.push(("APP_BAD KEY".to_string(), "1".to_string()));
+
+        let (exprs, source_text) = from_vars("APP", vars).unwrap();
+        assert_eq!(Value::deserialize(&mut exprs.into_iter(), &source_text).unwrap(), config());
+    }
+
+    #[test]
+    fn strings_round_trip() {
+        let values = ["x #y", "# all comment", "a = b", "=", "(", "f(x", ")", "line\nbreak", "(a\n# b\n)"];
+        let mut entries = values.iter().enumerate().map(|(i, v)| (format!("v{}", i), Value::String(v.to_string()))).collect_vec();
+        entries.push(("w".to_string(), Value::Integer(1.into())));
+        let map = Value::Map(entries.into_iter().collect());
+
+        let (exprs, source_text) = from_vars("APP", flatten(&map, "APP")).unwrap();
+        assert_eq!(Value::deserialize(&mut exprs.into_iter(), &source_text).unwrap(), map);
+    }
+
+    #[test]
+    fn values_and_prefixes_conflict() {
+        let error = from_vars("APP", pairs(&[("APP_A", "1"), ("APP_A__B", "2")])).unwrap_err();
+        assert!(matches!(error.kind(), Kind::DuplicateKey(k) if k == "APP_A__B"));
+    }
+}
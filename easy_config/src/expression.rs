@@ -1,65 +1,12 @@
-use std::fmt::Display;
+use std::borrow::Cow;
+use std::io::{self, Write};
+use crate::atom::quote;
 use crate::expression::ExpressionData::{List, BindingExpr, Presence};
 use crate::expression_iterator::ExpressionIterator;
+use crate::lexer::Lexer;
 use crate::lexical_span::LexicalSpan;
-
-#[derive(Debug, Clone, Ord, PartialOrd, Eq, PartialEq)]
-pub enum Atom {
-    Text(String),
-    Number(String),
-}
-
-impl Atom {
-    pub fn is_text(&self) -> bool {
-        matches!(self, Self::Text(_))
-    }
-    pub fn is_number(&self) -> bool {matches!(self, Self::Number(_))}
-}
-
-impl Display for Atom {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}", match self {
-            Atom::Text(s) | Atom::Number(s) => s
-        })
-    }
-}
-impl From<String> for Atom {
-    fn from(s: String) -> Self {
-        Atom::Text(s)
-    }
-}
-impl From<&str> for Atom {
-    fn from(s: &str) -> Self {
-        Atom::Text(s.to_string())
-    }
-}
-
-macro_rules! numeric_into_atom {
-    ($ty:ty) => {
-        impl From<$ty> for Atom {
-            fn from(value: $ty) -> Atom {
-                Atom::Number(value.to_string())
-            }
-        }
-    };
-}
-
-numeric_into_atom!(u8);
-numeric_into_atom!(u16);
-numeric_into_atom!(u32);
-numeric_into_atom!(u64);
-numeric_into_atom!(u128);
-numeric_into_atom!(usize);
-
-numeric_into_atom!(i8);
-numeric_into_atom!(i16);
-numeric_into_atom!(i32);
-numeric_into_atom!(i64);
-numeric_into_atom!(i128);
-numeric_into_atom!(isize);
-
-numeric_into_atom!(f32);
-numeric_into_atom!(f64);
+use crate::pretty::PrettyOptions;
+pub use crate::atom::Atom;
 
 #[derive(Debug, Clone,Ord, PartialOrd, Eq, PartialEq)]
 pub struct Binding {
@@ -67,6 +14,17 @@ pub struct Binding {
     pub value: Box<Expression>,
     pub span: LexicalSpan
 }
+impl Binding {
+    /// The name as it's written in a config. Names which aren't an identifier or an integer are
+    /// quoted.
+    pub fn written_name(&self) -> Cow<'_, str> {
+        let mut tokens = Lexer::new(&self.name);
+        match (tokens.next(), tokens.next()) {
+            (Some(token), None) if token.lexeme() == self.name && token.is_bare_name() => Cow::Borrowed(&self.name),
+            _ => Cow::Owned(quote(&self.name)),
+        }
+    }
+}
 #[derive(Debug, Clone,Ord, PartialOrd, Eq, PartialEq)]
 pub enum ExpressionData {
     Presence(Atom, LexicalSpan),
@@ -109,10 +67,6 @@ pub struct Expression {
     pub comment: Option<String>
 }
 
-fn indent(string: impl AsRef<str>, with:  impl AsRef<str>) -> String {
-    format!("{}{}", with.as_ref(), string.as_ref().replace("\n", &format!("\n{}", with.as_ref())))
-}
-
 pub fn escape(message: impl AsRef<str>) -> String {
     message
         .as_ref()
@@ -163,36 +117,54 @@ impl Expression {
     }
 
     pub fn uncomented_pretty(&self) -> String {
-        match &self.data {
-            Presence(s, _) => s.to_string(),
-            BindingExpr(b) => format!("{} = {}", b.name, b.value.pretty()),
-            List(c, _) => format!(
-                "(\n{}\n)",
-                indent(c.iter().map(|x| x.pretty()).collect::<Vec<String>>().join("\n"), "\t")
-            )
-        }
+        let mut uncommented = self.clone();
+        uncommented.comment = None;
+        uncommented.pretty()
     }
     pub fn pretty(&self) -> String {
-        if let Some(comment) = &self.comment {
-            format!("{}\n{}", indent(comment, "# "), self.uncomented_pretty())
-        } else {
-            self.uncomented_pretty()
-        }
+        self.pretty_with(&PrettyOptions::default())
     }
 
     pub fn uncomented_dump(&self) -> String {
+        let mut out = Vec::new();
+        self.write_uncommented_dump(&mut out).expect("writing to a Vec can not fail");
+        String::from_utf8(out).expect("dump only writes UTF-8")
+    }
+    pub fn dump(&self) -> String {
+        let mut out = Vec::new();
+        self.write_dump(&mut out).expect("writing to a Vec can not fail");
+        String::from_utf8(out).expect("dump only writes UTF-8")
+    }
+
+    fn write_uncommented_dump(&self, writer: &mut impl Write) -> io::Result<()> {
         match &self.data {
-            Presence(s, _) => s.to_string(),
-            BindingExpr(b) => format!("{} = {}", b.name, b.value.dump()),
-            List(c, _) => format!("({})", c.iter().map(|x| x.dump()).collect::<Vec<String>>().join(" "))
+            Presence(s, _) => write!(writer, "{}", s),
+            BindingExpr(b) => {
+                write!(writer, "{} = ", b.written_name())?;
+                b.value.write_dump(writer)
+            }
+            List(c, _) => {
+                writer.write_all(b"(")?;
+                for (index, x) in c.iter().enumerate() {
+                    if index > 0 {
+                        writer.write_all(b" ")?;
+                    }
+                    x.write_dump(writer)?;
+                }
+                writer.write_all(b")")
+            }
         }
     }
-    pub fn dump(&self) -> String {
+
+    /// Write this expression into `writer` on as few lines as possible, in a single pass.
+    pub fn write_dump(&self, writer: &mut impl Write) -> io::Result<()> {
         if let Some(comment) = &self.comment {
-            format!("{}\n{}", indent(comment, "# "), self.uncomented_dump())
-        } else {
-            self.uncomented_dump()
+            for line in comment.split('\n') {
+                writeln!(writer, "# {}", line)?;
+            }
         }
+
+        self.write_uncommented_dump(writer)
     }
 
     /// Convert this Expression to the smallest form which holds it. I.E. `Collection`s with 1 element
@@ -275,6 +247,53 @@ mod tests {
         assert_eq!(nested().dump(), String::from("alphabet = (a b c)"))
     }
 
+    #[test]
+    fn dump_comments() {
+        let commented = Expression::list(vec![
+            Expression::presence("a"),
+            Expression::presence("b").with_comment("one\ntwo"),
+        ]);
+        assert_eq!(commented.dump(), "(a # one\n# two\nb)");
+    }
+
+    #[test]
+    fn write_dump() {
+        let mut out = Vec::new();
+        more_nested().write_dump(&mut out).unwrap();
+        assert_eq!(String::from_utf8(out).unwrap(), more_nested().dump());
+    }
+
+    #[test]
+    fn write_pretty() {
+        let mut out = Vec::new();
+        more_nested().write_pretty(&mut out).unwrap();
+        assert_eq!(String::from_utf8(out).unwrap(), more_nested().pretty());
+    }
+
+    #[test]
+    fn names_are_quoted_when_needed() {
+        let names = Expression::list(vec![
+            Expression::binding("port", Expression::presence(1)),
+            Expression::binding("-8080", Expression::presence(2)),
+            Expression::binding("a.b", Expression::presence(3)),
+            Expression::binding("", Expression::presence(4)),
+        ]);
+        assert_eq!(names.dump(), "(port = 1 -8080 = 2 \"a.b\" = 3 \"\" = 4)");
+    }
+
+    #[test]
+    fn deep_nesting() {
+        let depth = 500;
+        let mut deep = Expression::presence("x");
+        for _ in 0..depth {
+            deep = Expression::list(vec![deep]);
+        }
+
+        let pretty = deep.pretty();
+        assert_eq!(pretty.lines().count(), 2 * depth + 1);
+        assert_eq!(deep.dump(), format!("{}x{}", "(".repeat(depth), ")".repeat(depth)));
+    }
+
     #[test]
     fn pretty() {
         assert_eq!(nested().pretty(), String::from("alphabet = (\n\ta\n\tb\n\tc\n)"))
@@ -288,14 +307,14 @@ mod tests {
     #[test]
     fn pretty_harder() {
         let make_pretty = Expression::list(vec![
-            Expression::binding("run_name".to_string(), Expression::presence("first".to_string())),
-            Expression::binding("names_in_run".to_string(), Expression::list(vec![
+            Expression::binding("run_name", Expression::presence("first".to_string())),
+            Expression::binding("names_in_run", Expression::list(vec![
                 Expression::list(vec![Expression::presence("HandMade".to_string()), Expression::presence("Ethan".to_string())]),
                 Expression::list(vec![Expression::presence("HandMade".to_string()), Expression::presence("James".to_string())]),
                 Expression::list(vec![Expression::presence("Generated".to_string()), Expression::presence("SDKJLHF".to_string())]),
                 Expression::list(vec![Expression::presence("Generated".to_string()), Expression::presence("Kerflooble".to_string())]),
             ])),
-            Expression::binding("count".to_string(), Expression::presence("2".to_string()))
+            Expression::binding("count", Expression::presence("2".to_string()))
         ]);
 
         assert_eq!(make_pretty.pretty(),
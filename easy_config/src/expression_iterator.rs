@@ -165,9 +165,8 @@ impl Iterator for ExpressionIterator {
     type Item = Expression;
     fn next(&mut self) -> Option<Self::Item> {
         let ret = self.inner.next();
-        ret.map(|x|{
+        ret.inspect(|x|{
             self.update_span(x.span());
-            x
         })
     }
     fn size_hint(&self) -> (usize, Option<usize>) {
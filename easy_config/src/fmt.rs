@@ -0,0 +1,548 @@
+use std::mem;
+use crate::lexer::Lexer;
+use crate::lexer::token::{Kind, Token};
+use crate::parser::Parser;
+use crate::parser::parser_error::ParserError;
+use crate::pretty::{PrettyOptions, Printer};
+
+/// The line width used by `format` when deciding whether a list fits on one line.
+pub const DEFAULT_MAX_WIDTH: usize = 100;
+
+/*
+The formatter works on its own syntax tree, built from the lossless token stream, instead of on
+`Expression`. An `Expression` holds at most one comment per node and has no notion of where that
+comment was written, so it cannot faithfully hold a hand-edited file.
+
+A comment is attached to the tree as follows:
+ - a comment on the same line as the end of an item trails that item,
+ - a comment on the same line as a `(` trails the `(`,
+ - any other comment leads the next item in the same list, or dangles at the end of the list if
+   there is no next item.
+*/
+struct Item {
+    leading: Vec<String>,
+    blank_line_before: bool,
+    node: Node,
+    trailing: Option<String>,
+}
+
+enum Node {
+    Atom(String),
+    Binding {
+        name: String,
+        value: Box<Node>,
+        // The list in `name = Some(thing)`, which the parser folds into the value.
+        applied: Option<List>,
+    },
+    List(List),
+}
+
+struct List {
+    open_comment: Option<String>,
+    items: Vec<Item>,
+    dangling: Vec<String>,
+}
+
+impl Item {
+    fn has_comments(&self) -> bool {
+        !self.leading.is_empty() || self.trailing.is_some() || self.node.has_comments()
+    }
+}
+
+impl Node {
+    fn has_comments(&self) -> bool {
+        match self {
+            Node::Atom(_) => false,
+            Node::Binding { value, applied, .. } =>
+                value.has_comments() || applied.as_ref().is_some_and(|x| x.has_comments()),
+            Node::List(l) => l.has_comments(),
+        }
+    }
+
+    fn binding_name_len(&self) -> Option<usize> {
+        match self {
+            Node::Binding { name, .. } => Some(name.chars().count()),
+            _ => None,
+        }
+    }
+
+    // The width of this node written on one line, or `None` if it is wider than `budget`.
+    fn inline_width(&self, budget: usize) -> Option<usize> {
+        let width = match self {
+            Node::Atom(a) => a.chars().count(),
+            Node::Binding { name, value, applied } => {
+                let mut width = name.chars().count() + " = ".len();
+                width += value.inline_width(budget.checked_sub(width)?)?;
+                if let Some(applied) = applied {
+                    width += applied.inline_width(budget.checked_sub(width)?)?;
+                }
+                width
+            }
+            Node::List(l) => l.inline_width(budget)?,
+        };
+
+        (width <= budget).then_some(width)
+    }
+}
+
+impl List {
+    fn has_comments(&self) -> bool {
+        self.open_comment.is_some() || !self.dangling.is_empty() || self.items.iter().any(|x| x.has_comments())
+    }
+
+    fn inline_width(&self, budget: usize) -> Option<usize> {
+        let mut width = "()".len() + self.items.len().saturating_sub(1);
+        for item in &self.items {
+            width += item.node.inline_width(budget.checked_sub(width)?)?;
+        }
+
+        (width <= budget).then_some(width)
+    }
+}
+
+struct TreeBuilder<'a> {
+    source: &'a str,
+    tokens: Vec<Token>,
+    index: usize,
+}
+
+impl<'a> TreeBuilder<'a> {
+    fn new(source: &'a str) -> Self {
+        Self {
+            source,
+            tokens: Lexer::lossless(source).collect(),
+            index: 0,
+        }
+    }
+
+    fn peek_kind(&self) -> Option<Kind> {
+        self.tokens.get(self.index).map(|x| x.kind())
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.index).cloned();
+        self.index += 1;
+        token
+    }
+
+    // The number of newlines between the token at `index` and the one before it.
+    fn newlines_before(&self, index: usize) -> usize {
+        if index == 0 || index >= self.tokens.len() {
+            return 0
+        }
+
+        let previous_end = self.tokens[index - 1].span().end();
+        let start = self.tokens[index].span().start();
+        self.source[previous_end..start].matches('\n').count()
+    }
+
+    fn comment(&mut self) -> String {
+        self.next().unwrap().lexeme().trim_end().to_string()
+    }
+
+    // Move every comment before the next non-comment token into `into`.
+    fn comments_into(&mut self, into: &mut Vec<String>) {
+        while self.peek_kind() == Some(Kind::Comment) {
+            let comment = self.comment();
+            into.push(comment);
+        }
+    }
+
+    fn next_non_comment_kind(&self) -> Option<Kind> {
+        self.tokens[self.index.min(self.tokens.len())..]
+            .iter()
+            .map(|x| x.kind())
+            .find(|x| *x != Kind::Comment)
+    }
+
+    fn items(&mut self) -> (Vec<Item>, Vec<String>) {
+        let mut items: Vec<Item> = vec![];
+        let mut pending = vec![];
+        let mut blank_line_before = false;
+
+        loop {
+            match self.peek_kind() {
+                None | Some(Kind::RParen) => return (items, pending),
+                Some(Kind::Comment) => {
+                    let on_new_line = self.newlines_before(self.index) > 0;
+                    let trails_last = pending.is_empty() && !on_new_line && items
+                        .last()
+                        .is_some_and(|x| x.trailing.is_none());
+
+                    if pending.is_empty() {
+                        blank_line_before = self.newlines_before(self.index) > 1;
+                    }
+
+                    let comment = self.comment();
+                    if trails_last {
+                        items.last_mut().unwrap().trailing = Some(comment);
+                    } else {
+                        pending.push(comment);
+                    }
+                }
+                Some(_) => {
+                    if pending.is_empty() {
+                        blank_line_before = self.newlines_before(self.index) > 1;
+                    }
+
+                    let mut hoisted = vec![];
+                    let node = self.node(&mut hoisted);
+                    pending.extend(hoisted);
+
+                    items.push(Item {
+                        leading: mem::take(&mut pending),
+                        blank_line_before: blank_line_before && !items.is_empty(),
+                        node,
+                        trailing: None,
+                    });
+                }
+            }
+        }
+    }
+
+    // Parse the node starting at the current token. Comments found in places where the layout
+    // has nowhere to keep them, such as between a binding's `=` and its value, are moved into
+    // `hoisted` to be written above the node.
+    fn node(&mut self, hoisted: &mut Vec<String>) -> Node {
+        let token = self.next().unwrap();
+        if token.kind() == Kind::LParen {
+            return Node::List(self.list())
+        }
+
+        if self.next_non_comment_kind() != Some(Kind::Equals) {
+            return Node::Atom(token.lexeme().to_string())
+        }
+
+        self.comments_into(hoisted);
+        self.next();
+        self.comments_into(hoisted);
+        let value = Box::new(self.node(hoisted));
+
+        let applied = if self.next_non_comment_kind() == Some(Kind::LParen) {
+            self.comments_into(hoisted);
+            self.next();
+            Some(self.list())
+        } else {
+            None
+        };
+
+        Node::Binding {
+            name: token.lexeme().to_string(),
+            value,
+            applied,
+        }
+    }
+
+    // Parse the rest of a list whose `(` has already been consumed.
+    fn list(&mut self) -> List {
+        let open_comment = if self.peek_kind() == Some(Kind::Comment) && self.newlines_before(self.index) == 0 {
+            Some(self.comment())
+        } else {
+            None
+        };
+
+        let (items, dangling) = self.items();
+        // The closing parenthesis.
+        self.next();
+
+        List {
+            open_comment,
+            items,
+            dangling,
+        }
+    }
+}
+
+struct Formatter<'a> {
+    printer: Printer<'a, Vec<u8>>,
+}
+
+impl Formatter<'_> {
+    fn fits_inline(&self, list: &List) -> bool {
+        let Some(max_width) = self.printer.options.max_width else { return false };
+        let Some(budget) = max_width.checked_sub(self.printer.column()) else { return false };
+        // Checking the width first bounds the work done by `has_comments` to what fits on a line.
+        list.inline_width(budget).is_some() && !list.has_comments()
+    }
+
+    // Bindings in a run of consecutive bindings, not broken by a blank line, are padded to the
+    // longest name in that run.
+    fn name_widths(&self, items: &[Item]) -> Vec<usize> {
+        let mut widths = vec![0; items.len()];
+        if !self.printer.options.align_equals {
+            return widths
+        }
+
+        let mut run: Vec<usize> = vec![];
+        let mut flush = |run: &mut Vec<usize>| {
+            let longest = run.iter().filter_map(|x| items[*x].node.binding_name_len()).max().unwrap_or(0);
+            for index in run.drain(..) {
+                widths[index] = longest;
+            }
+        };
+
+        for (index, item) in items.iter().enumerate() {
+            if item.blank_line_before || item.node.binding_name_len().is_none() {
+                flush(&mut run);
+            }
+            if item.node.binding_name_len().is_some() {
+                run.push(index);
+            }
+        }
+        flush(&mut run);
+
+        widths
+    }
+
+    fn item(&mut self, item: &Item, depth: usize, name_width: usize) {
+        for comment in &item.leading {
+            self.printer.push(comment);
+            self.printer.newline(depth);
+        }
+
+        self.node(&item.node, depth, name_width);
+
+        if let Some(comment) = &item.trailing {
+            self.printer.push(" ");
+            self.printer.push(comment);
+        }
+    }
+
+    fn node(&mut self, node: &Node, depth: usize, name_width: usize) {
+        match node {
+            Node::Atom(a) => self.printer.push(a),
+            Node::Binding { name, value, applied } => {
+                self.printer.push(name);
+                let padding = name_width.saturating_sub(name.chars().count());
+                self.printer.push(&" ".repeat(padding));
+                self.printer.push(" = ");
+                self.node(value, depth, 0);
+                if let Some(applied) = applied {
+                    self.list(applied, depth);
+                }
+            }
+            Node::List(l) => self.list(l, depth),
+        }
+    }
+
+    fn list(&mut self, list: &List, depth: usize) {
+        if list.items.is_empty() && !list.has_comments() {
+            self.printer.push("()");
+            return
+        }
+
+        if self.fits_inline(list) {
+            self.printer.push("(");
+            for (index, item) in list.items.iter().enumerate() {
+                if index > 0 {
+                    self.printer.push(" ");
+                }
+                self.node(&item.node, depth, 0);
+            }
+            self.printer.push(")");
+            return
+        }
+
+        self.printer.push("(");
+        if let Some(comment) = &list.open_comment {
+            self.printer.push(" ");
+            self.printer.push(comment);
+        }
+
+        let name_widths = self.name_widths(&list.items);
+        for (item, name_width) in list.items.iter().zip(name_widths) {
+            if item.blank_line_before {
+                self.printer.line_break();
+            }
+            self.printer.newline(depth + 1);
+            self.item(item, depth + 1, name_width);
+        }
+
+        for comment in &list.dangling {
+            self.printer.newline(depth + 1);
+            self.printer.push(comment);
+        }
+
+        self.printer.newline(depth);
+        self.printer.push(")");
+    }
+
+    fn file(&mut self, items: &[Item], dangling: &[String]) {
+        let name_widths = self.name_widths(items);
+        for (index, (item, name_width)) in items.iter().zip(name_widths).enumerate() {
+            if index > 0 {
+                if item.blank_line_before || self.printer.options.blank_lines_between_sections {
+                    self.printer.line_break();
+                }
+                self.printer.newline(0);
+            }
+            self.item(item, 0, name_width);
+        }
+
+        for (index, comment) in dangling.iter().enumerate() {
+            if index > 0 || !items.is_empty() {
+                self.printer.newline(0);
+            }
+            self.printer.push(comment);
+        }
+
+        if !self.printer.is_empty() {
+            self.printer.line_break();
+        }
+    }
+}
+
+fn default_options() -> PrettyOptions {
+    PrettyOptions::default().with_max_width(DEFAULT_MAX_WIDTH)
+}
+
+/// Reformat easy_config source text into the canonical layout, keeping every comment attached to
+/// the item it was written next to. The result parses to the same expression as `source`.
+pub fn format(source: impl AsRef<str>) -> Result<String, Vec<ParserError>> {
+    format_with(source, &default_options())
+}
+
+/// Reformat easy_config source text using the indentation, line width, and alignment described
+/// by `options`. The comment placement of `options` is ignored, since comments stay where the
+/// author put them.
+pub fn format_with(source: impl AsRef<str>, options: &PrettyOptions) -> Result<String, Vec<ParserError>> {
+    let source = source.as_ref();
+    let finished = Parser::new(source).parse();
+    if !finished.errors().is_empty() {
+        return Err(finished.errors().clone())
+    }
+
+    let mut builder = TreeBuilder::new(source);
+    let (items, dangling) = builder.items();
+
+    let mut formatter = Formatter { printer: Printer::new(options, Vec::new()) };
+    formatter.file(&items, &dangling);
+    let out = formatter.printer.finish().expect("writing to a Vec can not fail");
+    Ok(String::from_utf8(out).expect("the formatter only writes UTF-8"))
+}
+
+/// Check whether `source` is already in the layout `format` would produce.
+pub fn is_formatted(source: impl AsRef<str>) -> Result<bool, Vec<ParserError>> {
+    let source = source.as_ref();
+    Ok(format(source)? == source)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_formats_to(source: &str, expected: &str) {
+        let formatted = format(source).unwrap();
+        assert_eq!(formatted, expected);
+
+        let before = Parser::new(source).parse().unwrap();
+        let after = Parser::new(&formatted).parse().unwrap();
+        assert!(before.semantic_eq(&after), "formatting changed the meaning of:\n{}", source);
+        assert_eq!(format(&formatted).unwrap(), formatted, "formatting is not idempotent");
+    }
+
+    #[test]
+    fn layout() {
+        assert_formats_to(
+            "ip    =   1.2.3.4\nport=2\ncontent_folders = (   src/content\n  src/static)",
+            "ip = 1.2.3.4\nport = 2\ncontent_folders = (src/content src/static)\n"
+        );
+    }
+
+    #[test]
+    fn quoted_strings_are_kept() {
+        assert_formats_to("greeting=\"hello  (there)\"  hex = 0xFF", "greeting = \"hello  (there)\"\nhex = 0xFF\n");
+    }
+
+    #[test]
+    fn long_lists_are_broken() {
+        let options = PrettyOptions::default().with_max_width(20);
+        assert_eq!(
+            format_with("folders = (src/content src/static)", &options).unwrap(),
+            "folders = (\n\tsrc/content\n\tsrc/static\n)\n"
+        );
+    }
+
+    #[test]
+    fn leading_comments() {
+        assert_formats_to(
+            "# The address.\n  # Really.\nip = 1.2.3.4\n\n\n# The port.\nport = 2",
+            "# The address.\n# Really.\nip = 1.2.3.4\n\n# The port.\nport = 2\n"
+        );
+    }
+
+    #[test]
+    fn trailing_comments() {
+        assert_formats_to(
+            "ip = 1.2.3.4    # The address.\nport = 2 #The port.",
+            "ip = 1.2.3.4 # The address.\nport = 2 #The port.\n"
+        );
+    }
+
+    #[test]
+    fn comments_inside_lists() {
+        assert_formats_to(
+            "nested = ( # open\n  one = 1\n    # a comment goes here\n  two = 2 # or here\n   # at the end\n)",
+            "nested = ( # open\n\tone = 1\n\t# a comment goes here\n\ttwo = 2 # or here\n\t# at the end\n)\n"
+        );
+    }
+
+    #[test]
+    fn trailing_comment_after_list() {
+        assert_formats_to(
+            "names = (\n  a\n  b\n) # names\nx = 1",
+            "names = (a b) # names\nx = 1\n"
+        );
+    }
+
+    #[test]
+    fn hoisted_comments() {
+        assert_formats_to(
+            "x = # the value\n  1",
+            "# the value\nx = 1\n"
+        );
+    }
+
+    #[test]
+    fn applied_lists() {
+        assert_formats_to("bind   =   Some(  thing )", "bind = Some(thing)\n");
+    }
+
+    #[test]
+    fn comment_only_file() {
+        assert_formats_to("# just\n\n# comments   ", "# just\n# comments\n");
+    }
+
+    #[test]
+    fn aligned() {
+        let options = default_options().with_aligned_equals(true);
+        assert_eq!(
+            format_with("address = localhost\nport = 1\n\nx = 2\nlonger_name = 3", &options).unwrap(),
+            "address = localhost\nport    = 1\n\nx           = 2\nlonger_name = 3\n"
+        );
+    }
+
+    #[test]
+    fn escapes_are_kept() {
+        assert_formats_to(
+            r"escaped_characters = (
+    \(
+    \)
+    \=
+    \\
+)",
+            "escaped_characters = (\\( \\) \\= \\\\)\n"
+        );
+    }
+
+    #[test]
+    fn is_formatted_check() {
+        assert!(is_formatted("x = 1\n").unwrap());
+        assert!(!is_formatted("x=1").unwrap());
+    }
+
+    #[test]
+    fn parse_errors() {
+        assert!(format("x = (a b").is_err());
+    }
+}
@@ -0,0 +1,99 @@
+//! `.env` files: one `KEY=value` per line, with `#` comments and an optional `export` in front
+//! of each key.
+
+use crate::expression::Expression;
+use crate::import::import_error::{ImportError, Kind};
+use crate::lexical_span::LexicalSpan;
+
+const COMMENT: &[char] = &['#'];
+
+/// Read a `.env` file as a list of bindings. Keys are lower cased to match field names, since
+/// environment variables are conventionally upper case. Values in double quotes may use the same
+/// escapes as easy_config strings, and values in single quotes are taken literally.
+pub fn parse(source_text: impl AsRef<str>) -> Result<Expression, ImportError> {
+    let source_text = source_text.as_ref();
+    let mut bindings = Vec::new();
+
+    for line in super::lines(source_text) {
+        if line.is_blank_or_comment(COMMENT) {
+            continue
+        }
+
+        let (text, start) = match line.text.strip_prefix("export") {
+            Some(rest) if rest.starts_with(char::is_whitespace) => (rest, line.start + "export".len()),
+            _ => (line.text, line.start),
+        };
+        let Some(equals) = text.find('=') else {
+            return Err(super::error(Kind::ExpectedEquals(line.text.to_string()), line.span(), source_text))
+        };
+
+        let (name, name_span) = super::key(&text[..equals], start, source_text)?;
+        let value = super::value(&text[equals + 1..], start + equals + 1, source_text, COMMENT)?;
+        let span = LexicalSpan::new(name_span.start(), value.span().end().max(start + equals + 1));
+        super::insert(&mut bindings, name.to_lowercase(), value, span);
+    }
+
+    Ok(Expression::list(bindings).with_span(LexicalSpan::new(0, source_text.len())))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::atom::Atom;
+    use crate::serialization::deserialize_field_from_map_or_error;
+    use super::*;
+
+    fn binding(name: &str, value: impl Into<Atom>) -> Expression {
+        Expression::binding(name, Expression::presence(value))
+    }
+
+    #[test]
+    fn values() {
+        let source = "# settings\nPORT=8080\nexport DEBUG=true\nNAME=\"my app\\n\" # the name\nPATH_GLOB='*.rs # not a comment'\nEMPTY=\n";
+        assert!(parse(source).unwrap().semantic_eq(&Expression::list(vec![
+            binding("port", 8080),
+            binding("debug", true),
+            binding("name", Atom::Quoted("my app\n".to_string())),
+            binding("path_glob", Atom::Quoted("*.rs # not a comment".to_string())),
+            binding("empty", Atom::Quoted(String::new())),
+        ])));
+    }
+
+    #[test]
+    fn quotes_written_by_a_shell() {
+        let exprs = parse("A='it'\\''s' # mine\nB='\\'\\'''").unwrap();
+        assert!(exprs.semantic_eq(&Expression::list(vec![
+            binding("a", Atom::Quoted("it's".to_string())),
+            binding("b", Atom::Quoted("\\'".to_string())),
+        ])));
+    }
+
+    #[test]
+    fn later_values_win() {
+        let exprs = parse("A=1\nA=2").unwrap();
+        assert!(exprs.semantic_eq(&Expression::list(vec![binding("a", 2)])));
+    }
+
+    #[test]
+    fn strings_deserialize_from_the_file() {
+        let source = "GREETING = hello there # friendly\nQUOTED=\"a (b)\"\n";
+        let (mut mapping, span) = parse(source).unwrap().into_iter()
+            .convert_binding_list_to_hashmap_of_values(source).unwrap();
+        let greeting: String = deserialize_field_from_map_or_error("greeting", &mut mapping, span, source).unwrap();
+        let quoted: String = deserialize_field_from_map_or_error("quoted", &mut mapping, span, source).unwrap();
+        assert_eq!(greeting, "hello there");
+        assert_eq!(quoted, "a (b)");
+    }
+
+    #[test]
+    fn errors_point_into_the_file() {
+        let error = parse("A=1\nnot a binding\n").unwrap_err();
+        assert!(matches!(error.kind(), Kind::ExpectedEquals(_)));
+        assert!(error.to_string().starts_with("Error at 2:1"));
+
+        let error = parse("A=\"open").unwrap_err();
+        assert!(matches!(error.kind(), Kind::UnterminatedString(_)));
+
+        let error = parse("TWO WORDS=1").unwrap_err();
+        assert!(matches!(error.kind(), Kind::InvalidKey(_)));
+    }
+}
@@ -0,0 +1,27 @@
+use crate::config_error::ConfigError;
+use crate::config_error::describe::Describe;
+
+pub type ImportError = ConfigError<Kind>;
+
+#[derive(Debug, Clone)]
+pub enum Kind {
+    ExpectedEquals(String),
+    InvalidKey(String),
+    UnterminatedString(String),
+    InvalidEscape(String),
+    TrailingText(String),
+    UnterminatedSection(String),
+}
+
+impl Describe for Kind {
+    fn describe(&self) -> String {
+        match self {
+            Kind::ExpectedEquals(line) => format!("Expected KEY=value, but got {}.", line),
+            Kind::InvalidKey(key) => format!("{:?} can not be used as a key, since it is not a single word.", key),
+            Kind::UnterminatedString(value) => format!("The quoted string {} is missing its closing quote.", value),
+            Kind::InvalidEscape(value) => format!("Invalid unicode escape in the string {}.", value),
+            Kind::TrailingText(text) => format!("Unexpected {} after the closing quote.", text),
+            Kind::UnterminatedSection(line) => format!("The section header {} is missing its closing ']'.", line),
+        }
+    }
+}
@@ -0,0 +1,114 @@
+//! INI files: `key = value` lines grouped under `[section]` headers, with `;` and `#` comments.
+
+use crate::atom::Atom;
+use crate::expression::{Expression, ExpressionData};
+use crate::import::import_error::{ImportError, Kind};
+use crate::lexical_span::LexicalSpan;
+
+const COMMENT: &[char] = &[';', '#'];
+
+struct Section {
+    name: String,
+    span: LexicalSpan,
+    entries: Vec<Expression>,
+}
+
+/// Read an INI file as a list of bindings. Keys before the first header are bound at the top
+/// level, and each section is bound to a list of its keys, so `[server]` followed by `port = 80`
+/// reads like `server = (port = 80)`. Sections which appear twice are merged.
+///
+/// Keys may be separated from their values by `=` or `:`, and a key on its own is a flag.
+pub fn parse(source_text: impl AsRef<str>) -> Result<Expression, ImportError> {
+    let source_text = source_text.as_ref();
+    let mut top_level = Vec::new();
+    let mut sections: Vec<Section> = Vec::new();
+    let mut current = None;
+
+    for line in super::lines(source_text) {
+        if line.is_blank_or_comment(COMMENT) {
+            continue
+        }
+
+        if let Some(header) = line.text.strip_prefix('[') {
+            let Some(close) = header.find(']') else {
+                return Err(super::error(Kind::UnterminatedSection(line.text.to_string()), line.span(), source_text))
+            };
+            let (name, span) = super::key(&header[..close], line.start + 1, source_text)?;
+            current = Some(match sections.iter().position(|x| x.name == name) {
+                Some(index) => index,
+                None => {
+                    sections.push(Section { name, span, entries: Vec::new() });
+                    sections.len() - 1
+                }
+            });
+            continue
+        }
+
+        let entries = match current {
+            Some(index) => &mut sections[index].entries,
+            None => &mut top_level,
+        };
+        match line.text.find(['=', ':']) {
+            Some(separator) => {
+                let (name, name_span) = super::key(&line.text[..separator], line.start, source_text)?;
+                let value_start = line.start + separator + 1;
+                let value = super::value(&line.text[separator + 1..], value_start, source_text, COMMENT)?;
+                let span = LexicalSpan::new(name_span.start(), value.span().end().max(value_start));
+                super::insert(entries, name, value, span);
+            }
+            None => {
+                let flag = &line.text[..super::comment_start(line.text, COMMENT)];
+                let (name, span) = super::key(flag, line.start, source_text)?;
+                if !entries.iter().any(|x| matches!(&x.data, ExpressionData::Presence(Atom::Text(f), _) if *f == name)) {
+                    entries.push(Expression::presence(Atom::Text(name)).with_span(span));
+                }
+            }
+        }
+    }
+
+    top_level.extend(sections.into_iter().map(|section| {
+        let body = Expression::list(section.entries).with_span(section.span);
+        Expression::binding(section.name, body).with_span(section.span)
+    }));
+    Ok(Expression::list(top_level).with_span(LexicalSpan::new(0, source_text.len())))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn binding(name: &str, value: impl Into<Atom>) -> Expression {
+        Expression::binding(name, Expression::presence(value))
+    }
+
+    #[test]
+    fn sections() {
+        let source = "; global settings\nname = demo\n\n[server]\nhost: localhost\nport = 8080 ; the default\nverbose\n\n[client]\nretries = 3\n[server]\nport = 9090\n";
+        assert!(parse(source).unwrap().semantic_eq(&Expression::list(vec![
+            binding("name", "demo"),
+            Expression::binding("server", Expression::list(vec![
+                binding("host", "localhost"),
+                binding("port", 9090),
+                Expression::presence("verbose"),
+            ])),
+            Expression::binding("client", Expression::list(vec![
+                binding("retries", 3),
+            ])),
+        ])));
+    }
+
+    #[test]
+    fn values_with_separators() {
+        let exprs = parse("url = http://example.com:80/a;b").unwrap();
+        assert!(exprs.semantic_eq(&Expression::list(vec![
+            binding("url", Atom::Text("http://example.com:80/a;b".to_string())),
+        ])));
+    }
+
+    #[test]
+    fn unterminated_section() {
+        let error = parse("a = 1\n[server\n").unwrap_err();
+        assert!(matches!(error.kind(), Kind::UnterminatedSection(_)));
+        assert!(error.to_string().starts_with("Error at 2:1"));
+    }
+}
@@ -0,0 +1,146 @@
+//! Importers for the formats older tools tend to ship: `.env` files and INI files.
+//!
+//! Each importer turns a file into a list of bindings, which deserializes the same way a native
+//! file holding those bindings would. Spans point into the imported text, so pass that text as
+//! the `source_text` when deserializing and errors will show the offending line of the file.
+//!
+//! ```ignore
+//! let source = std::fs::read_to_string(".env")?;
+//! let exprs = easy_config::import::dotenv::parse(&source)?;
+//! let config = Config::deserialize(&mut exprs.into_iter(), &source)?;
+//! ```
+
+pub mod import_error;
+pub mod dotenv;
+pub mod ini;
+
+use crate::atom::{unquote, Atom, QuoteError};
+use crate::expression::{Expression, ExpressionData};
+use crate::import::import_error::{ImportError, Kind};
+use crate::lexical_span::LexicalSpan;
+
+/// A line of an imported file, without its surrounding whitespace.
+struct Line<'a> {
+    text: &'a str,
+    start: usize,
+}
+impl Line<'_> {
+    fn span(&self) -> LexicalSpan {
+        LexicalSpan::new(self.start, self.start + self.text.len())
+    }
+
+    fn is_blank_or_comment(&self, comment_chars: &[char]) -> bool {
+        self.text.is_empty() || self.text.starts_with(comment_chars)
+    }
+}
+
+fn lines(source_text: &str) -> impl Iterator<Item = Line<'_>> {
+    let mut offset = 0;
+    source_text.split('\n').map(move |raw| {
+        let start = offset + raw.len() - raw.trim_start().len();
+        offset += raw.len() + 1;
+        Line { text: raw.trim(), start }
+    })
+}
+
+fn error(kind: Kind, span: LexicalSpan, source_text: &str) -> ImportError {
+    ImportError::on_span(kind, span, source_text)
+}
+
+/// Read the key at `text`, which starts at `start` in `source_text`.
+fn key(text: &str, start: usize, source_text: &str) -> Result<(String, LexicalSpan), ImportError> {
+    let key = text.trim();
+    let start = start + text.len() - text.trim_start().len();
+    let span = LexicalSpan::new(start, start + key.len());
+
+    if !Atom::string(key).is_text() {
+        return Err(error(Kind::InvalidKey(key.to_string()), span, source_text))
+    }
+    Ok((key.to_string(), span))
+}
+
+// The index of the first comment character which starts a word, so `a#b` stays intact.
+fn comment_start(text: &str, comment_chars: &[char]) -> usize {
+    let mut previous = ' ';
+    for (index, c) in text.char_indices() {
+        if comment_chars.contains(&c) && previous.is_whitespace() {
+            return index
+        }
+        previous = c;
+    }
+    text.len()
+}
+
+const SHELL_QUOTE: &str = r"'\''";
+
+// The index of the quote closing the string at the start of `text`.
+fn closing_quote(text: &str, quote: char) -> Option<usize> {
+    let mut escaped = false;
+    let mut chars = text.char_indices().skip(1);
+    while let Some((index, c)) = chars.next() {
+        if c == quote && !escaped {
+            // A shell writes a single quote inside single quotes as `'\''`, which closes the
+            // quotes, escapes a quote, and opens them again.
+            if quote == '\'' && text[index..].starts_with(SHELL_QUOTE) {
+                chars.nth(SHELL_QUOTE.len() - 2);
+                continue
+            }
+            return Some(index)
+        }
+        escaped = quote == '"' && !escaped && c == '\\';
+    }
+    None
+}
+
+/// Read the value at `text`, which starts at `start` in `source_text`. Quoted values become
+/// quoted strings whose span covers their contents, and anything else is typed the way a native
+/// atom would be.
+fn value(text: &str, start: usize, source_text: &str, comment_chars: &[char]) -> Result<Expression, ImportError> {
+    let start = start + text.len() - text.trim_start().len();
+    let text = text.trim();
+
+    if let Some(quote) = text.chars().next().filter(|c| *c == '"' || *c == '\'') {
+        let Some(end) = closing_quote(text, quote) else {
+            return Err(error(Kind::UnterminatedString(text.to_string()), LexicalSpan::new(start, start + text.len()), source_text))
+        };
+        let trailing = text[end + 1..].trim_start();
+        if !trailing.is_empty() && !trailing.starts_with(comment_chars) {
+            let trailing_start = start + text.len() - trailing.len();
+            return Err(error(Kind::TrailingText(trailing.to_string()), LexicalSpan::new(trailing_start, start + text.len()), source_text))
+        }
+
+        let contents = if quote == '"' {
+            unquote(&text[..=end]).map_err(|e| match e {
+                QuoteError::Unterminated => error(Kind::UnterminatedString(text.to_string()), LexicalSpan::new(start, start + end + 1), source_text),
+                QuoteError::InvalidUnicodeEscape => error(Kind::InvalidEscape(text[..=end].to_string()), LexicalSpan::new(start, start + end + 1), source_text),
+            })?
+        } else {
+            text[1..end].replace(SHELL_QUOTE, "'")
+        };
+        return Ok(Expression::presence(Atom::Quoted(contents)).with_span(LexicalSpan::new(start + 1, start + end)))
+    }
+
+    let raw = text[..comment_start(text, comment_chars)].trim_end();
+    let atom = match Atom::classify(raw) {
+        _ if raw.is_empty() => Atom::Quoted(String::new()),
+        Atom::Text(text) => Atom::string(text),
+        other => other,
+    };
+    Ok(Expression::presence(atom).with_span(LexicalSpan::new(start, start + raw.len())))
+}
+
+/// Add a binding to `entries`. Like most tools reading these formats, a later value for the same
+/// key replaces an earlier one.
+fn insert(entries: &mut Vec<Expression>, name: String, value: Expression, span: LexicalSpan) {
+    let index = entries.iter().position(|x| match &x.data {
+        ExpressionData::BindingExpr(b) => b.name == name,
+        ExpressionData::Presence(Atom::Text(flag), _) => *flag == name,
+        _ => false,
+    });
+
+    let binding = Expression::binding(name, value).with_span(span);
+    match index {
+        Some(index) => entries[index] = binding,
+        None => entries.push(binding),
+    }
+}
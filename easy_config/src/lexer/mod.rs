@@ -1,6 +1,5 @@
-use std::iter::{Enumerate, Peekable};
-use std::str::Chars;
-use itertools::Itertools;
+use std::iter::Peekable;
+use std::str::CharIndices;
 use crate::lexer::token::{Kind, Token};
 
 pub mod token;
@@ -11,37 +10,27 @@ fn is_token_boundary(c: char) -> bool {
 }
 
 pub(crate) struct Lexer<'a> {
-    characters: Peekable<Enumerate<Chars<'a>>>
-}
-
-fn potentially_numeric(character: char) -> bool {
-    character.is_numeric() ||
-    character == 'x'       ||
-    character == 'b'       ||
-    character == 'e'       ||
-    character == '.'
+    input: &'a str,
+    characters: Peekable<CharIndices<'a>>,
+    keep_comments: bool,
 }
 
 impl<'a> Lexer<'a> {
     pub(crate) fn new(input: &'a str) -> Self {
         Self {
-            characters: input.chars().enumerate().peekable(),
+            input,
+            characters: input.char_indices().peekable(),
+            keep_comments: false,
         }
     }
 
-    fn take_chars_while(&mut self, func: fn(char) -> bool) -> Vec<char> {
-        let mut result = Vec::new();
-
-        while let Some((_, c)) = self.characters.peek() {
-            if func(*c) {
-                result.push(self.characters.next().unwrap().1);
-            }
-            else {
-                break
-            }
+    /// A lexer which also emits `Comment` tokens, so that no part of the input except whitespace
+    /// is lost. Whitespace can be recovered from the gaps between token spans.
+    pub(crate) fn lossless(input: &'a str) -> Self {
+        Self {
+            keep_comments: true,
+            ..Self::new(input)
         }
-
-        result
     }
 
     fn skip_chars_while(&mut self, func: fn(char) -> bool) {
@@ -54,34 +43,59 @@ impl<'a> Lexer<'a> {
         }
     }
 
+    // The `=` of an inclusive range such as `1..=5` is part of the token, rather than making it a
+    // binding.
+    fn at_inclusive_range(&self, text: &str, index: usize, c: char) -> bool {
+        c == '=' && text.ends_with("..") && self.input[index + 1..].starts_with(|x: char| x.is_numeric() || x == '-')
+    }
+
+    // Eat up to the next unescaped token boundary. A backslash escapes whatever character follows
+    // it, including another backslash.
     fn eat_text(&mut self, first_char: char) -> String {
         let mut text = String::from(first_char);
+        let mut escaped = first_char == '\\';
+
+        while let Some((index, c)) = self.characters.peek() {
+            let (index, c) = (*index, *c);
+            if !escaped && is_token_boundary(c) && !self.at_inclusive_range(&text, index, c) {
+                break
+            }
 
-        loop {
-            let ate = self
-                .take_chars_while(|x| !is_token_boundary(x))
-                .iter()
-                .join("");
-            text.push_str(&ate);
+            escaped = !escaped && c == '\\';
+            text.push(c);
+            self.characters.next();
+        }
 
+        text
+    }
 
-            assert!(!text.is_empty());
-            if text.chars().last().unwrap() != '\\' { break }
+    // Eat up to and including the closing quote. Token boundaries inside the quotes don't end
+    // the token, and neither does an escaped quote. If there is no closing quote the token runs to
+    // the end of the input, and the parser reports it.
+    fn eat_quoted(&mut self) -> String {
+        let mut text = String::from('"');
+        let mut escaped = false;
 
-            let Some((_, last_char)) = self.characters.next() else { break };
-            text.push(last_char);
+        for (_, c) in self.characters.by_ref() {
+            text.push(c);
+            if !escaped && c == '"' {
+                break
+            }
+            escaped = !escaped && c == '\\';
         }
 
         text
     }
 
     fn emit_multi_char_token(&mut self, first_char: char, starting_index: usize) -> Token {
+        if first_char == '"' {
+            return Token::new(Kind::Quoted, starting_index, self.eat_quoted());
+        }
+
+        // Numbers run to the next token boundary, the same as text. Working out what sort of
+        // number it is, if any, is left to `Atom::classify`.
         if first_char.is_numeric() || first_char == '-' {
-            let remainder = self
-                .take_chars_while(|c| potentially_numeric(c))
-                .iter()
-                .join("");
-            return Token::new(Kind::Number, starting_index, format!("{}{}", first_char, remainder));
+            return Token::new(Kind::Number, starting_index, self.eat_text(first_char));
         }
 
         Token::new(Kind::Text, starting_index, self.eat_text(first_char))
@@ -91,13 +105,16 @@ impl<'a> Lexer<'a> {
         self.skip_chars_while(|x| x.is_whitespace());
     }
 
-    fn eat_comment(&mut self) {
-        if self.characters.peek().map(|(_, x)| *x != '#').unwrap_or(true) {
-            return;
-        }
+    fn at_comment(&mut self) -> bool {
+        self.characters.peek().map(|(_, x)| *x == '#').unwrap_or(false)
+    }
+
+    // Eat everything up to, but not including, the next newline.
+    fn eat_comment(&mut self) -> Token {
+        let start = self.characters.peek().map(|(i, _)| *i).unwrap_or(self.input.len());
         self.skip_chars_while(|x| x != '\n');
-        // skip the \n left untouched from above
-        self.characters.next();
+        let end = self.characters.peek().map(|(i, _)| *i).unwrap_or(self.input.len());
+        Token::new(Kind::Comment, start, &self.input[start..end])
     }
 }
 
@@ -105,11 +122,17 @@ impl Iterator for Lexer<'_> {
     type Item = Token;
     fn next(&mut self) -> Option<Self::Item> {
         self.eat_whitespace();
-        self.eat_comment();
+        while self.at_comment() {
+            let comment = self.eat_comment();
+            if self.keep_comments {
+                return Some(comment)
+            }
+            self.eat_whitespace();
+        }
 
         // It's not safe to unwrap here, since the loop above could have terminated because peek
         // (and therefore also next) is None.
-        let Some((next_index, next_char)) = self.characters.next() else { return None };
+        let (next_index, next_char) = self.characters.next()?;
 
 
         Some(match next_char {
@@ -128,7 +151,9 @@ mod tests {
     #[test]
     fn eat_rocket() {
         let mut x = Lexer::new("rocket beans");
-        assert_eq!(x.take_chars_while(|x| !is_token_boundary(x)).iter().join(""), "rocket")
+        let (_, first) = x.characters.next().unwrap();
+        assert_eq!(x.eat_text(first), "rocket");
+        assert_eq!(x.characters.next(), Some((6, ' ')));
     }
 
     #[test]
@@ -173,6 +198,33 @@ mod tests {
         assert_eq!(lexer.next(), Some(Token::new(Kind::RParen, 21, ")")));
     }
 
+    #[test]
+    fn consecutive_comments() {
+        let text = "# one\n# two\n\n   # three\na";
+        let tokens: Vec<Token> = Lexer::new(text).collect();
+        assert_eq!(tokens, vec![Token::new(Kind::Text, 24, "a")]);
+    }
+
+    #[test]
+    fn lossless_keeps_comments() {
+        let text = "a # one\n# two\nb";
+        let tokens: Vec<Token> = Lexer::lossless(text).collect();
+        assert_eq!(tokens, vec![
+            Token::new(Kind::Text, 0, "a"),
+            Token::new(Kind::Comment, 2, "# one"),
+            Token::new(Kind::Comment, 8, "# two"),
+            Token::new(Kind::Text, 14, "b"),
+        ]);
+    }
+
+    #[test]
+    fn spans_are_byte_offsets() {
+        let text = "é = ü b";
+        let tokens: Vec<Token> = Lexer::new(text).collect();
+        assert_eq!(tokens[2].span().slice(text), "ü");
+        assert_eq!(tokens[3].span().slice(text), "b");
+    }
+
     #[test]
     fn rocket() {
         let text = "rocket";
@@ -217,6 +269,59 @@ mod tests {
         assert_eq!(lexer.next(), Some(Token::new(Kind::Text, 0, "a\\(b")));
     }
 
+    #[test]
+    fn escaped_backslash() {
+        let text = "\\\\\n)";
+        let tokens: Vec<Token> = Lexer::new(text).collect();
+        assert_eq!(tokens, vec![
+            Token::new(Kind::Text, 0, "\\\\"),
+            Token::new(Kind::RParen, 3, ")"),
+        ]);
+    }
+
+    #[test]
+    fn numbers_run_to_boundary() {
+        let text = "127.0.0.1 30s -0x1F)";
+        let tokens: Vec<Token> = Lexer::new(text).collect();
+        assert_eq!(tokens, vec![
+            Token::new(Kind::Number, 0, "127.0.0.1"),
+            Token::new(Kind::Number, 10, "30s"),
+            Token::new(Kind::Number, 14, "-0x1F"),
+            Token::new(Kind::RParen, 19, ")"),
+        ]);
+    }
+
+    #[test]
+    fn ranges() {
+        let text = "1..5 1..=5 ..=-5 a..= 5";
+        let tokens: Vec<Token> = Lexer::new(text).collect();
+        assert_eq!(tokens, vec![
+            Token::new(Kind::Number, 0, "1..5"),
+            Token::new(Kind::Number, 5, "1..=5"),
+            Token::new(Kind::Text, 11, "..=-5"),
+            Token::new(Kind::Text, 17, "a.."),
+            Token::new(Kind::Equals, 20, "="),
+            Token::new(Kind::Number, 22, "5"),
+        ]);
+    }
+
+    #[test]
+    fn quoted() {
+        let text = r#"a "b (= c) \" d" "#;
+        let tokens: Vec<Token> = Lexer::new(text).collect();
+        assert_eq!(tokens, vec![
+            Token::new(Kind::Text, 0, "a"),
+            Token::new(Kind::Quoted, 2, r#""b (= c) \" d""#),
+        ]);
+    }
+
+    #[test]
+    fn unterminated_quote() {
+        let text = "\"open (";
+        let tokens: Vec<Token> = Lexer::new(text).collect();
+        assert_eq!(tokens, vec![Token::new(Kind::Quoted, 0, "\"open (")]);
+    }
+
     #[test]
     fn newline_inside() {
         let text = "a\nb";
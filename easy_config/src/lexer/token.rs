@@ -1,4 +1,5 @@
 use std::fmt::Display;
+use crate::atom::Atom;
 use crate::lexical_span::LexicalSpan;
 use crate::parser::parser_error::{end_of_input, ParserError};
 
@@ -8,7 +9,9 @@ pub enum Kind {
     RParen,
     Text,
     Number,
+    Quoted,
     Equals,
+    Comment,
     Eoi
 }
 
@@ -19,7 +22,9 @@ impl Display for Kind {
             Kind::RParen => "Right Parenthesis",
             Kind::Text => "Text",
             Kind::Number => "Number",
+            Kind::Quoted => "Quoted String",
             Kind::Equals => "Equals",
+            Kind::Comment => "Comment",
             Kind::Eoi => "End of Input"
         })
     }
@@ -74,6 +79,16 @@ impl Token {
         None
     }
 
+    /// Whether this token can be used as a binding name as written: an identifier, or an
+    /// integer so that maps can be keyed by numbers.
+    pub fn is_bare_name(&self) -> bool {
+        match self.kind {
+            Kind::Text => self.invalid_identifier_char_index().is_none(),
+            Kind::Number => matches!(Atom::classify(&self.lexeme), Atom::Integer(_)),
+            _ => false,
+        }
+    }
+
     pub fn new_eoi(at: usize) -> Self {
         Self {
             kind: Kind::Eoi,
@@ -31,15 +31,21 @@ impl LexicalSpan {
         }
     }
 
+    /// The 1-based row and column at which this span starts.
     pub fn find_row_and_column(&self, source_text: impl AsRef<str>) -> (usize, usize) {
         let source_text = source_text.as_ref();
+        let mut start = self.start.min(source_text.len());
+        while !source_text.is_char_boundary(start) {
+            start -= 1;
+        }
+
         let mut row = 1;
-        let mut col = 0;
+        let mut col = 1;
 
-        for chr in source_text.chars() {
+        for chr in source_text[..start].chars() {
             if chr == '\n' {
                 row += 1;
-                col = 0;
+                col = 1;
             } else {
                 col += 1;
             }
@@ -53,6 +59,20 @@ impl IntoIterator for LexicalSpan {
     type Item = usize;
     type IntoIter = core::ops::Range<usize>;
     fn into_iter(self) -> Self::IntoIter {
-        (self.start..self.end).into_iter()
+        self.start..self.end
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn row_and_column() {
+        let text = "a = b\nc = dé\ne";
+        assert_eq!(LexicalSpan::new(0, 1).find_row_and_column(text), (1, 1));
+        assert_eq!(LexicalSpan::new(4, 5).find_row_and_column(text), (1, 5));
+        assert_eq!(LexicalSpan::new(10, 11).find_row_and_column(text), (2, 5));
+        assert_eq!(LexicalSpan::new(14, 15).find_row_and_column(text), (3, 1));
+    }
+}
@@ -1,4 +1,5 @@
 mod lexer;
+pub mod atom;
 pub mod expression;
 pub mod expression_iterator;
 #[allow(dead_code)]
@@ -6,8 +7,27 @@ pub mod parser;
 pub mod serialization;
 pub mod lexical_span;
 pub mod config_error;
+pub mod canonical;
+pub mod pretty;
+pub mod fmt;
+pub mod value;
+pub mod import;
+pub mod env;
+#[cfg(feature = "serde")]
+pub mod serde;
+#[cfg(any(feature = "json", feature = "toml", feature = "yaml"))]
+pub mod convert;
 
+pub use value::Value;
 
 
+
+#[cfg(feature = "derive")]
+pub use easy_config_derive::EasyConfig;
+/// Build an [`Expression`](expression::Expression) from easy_config syntax written inline.
+///
+/// Each atom is classified with [`Atom::classify`](atom::Atom::classify), so it holds the same
+/// value the parser would give it. A binding name which the parser wouldn't accept bare, such as
+/// `1.5`, is quoted when the expression is written, so the output always reads back.
 #[cfg(feature = "derive")]
-pub use easy_config_derive::EasyConfig;
\ No newline at end of file
+pub use easy_config_derive::ec;
\ No newline at end of file
@@ -1,5 +1,6 @@
 use itertools::Itertools;
 use crate::config_error::Contextualize;
+use crate::atom::{unquote, QuoteError};
 use crate::expression::{Atom, Expression};
 use crate::lexer::{token, Lexer};
 use crate::lexer::token::{Kind, Token};
@@ -14,10 +15,10 @@ pub struct FinishedParser {
 
 impl From<Parser> for FinishedParser {
     fn from(parser: Parser) -> Self {
-        let span = LexicalSpan::new(
-            parser.expressions.first().unwrap().span().start(),
-            parser.expressions.last().unwrap().span().end()
-        );
+        let span = match (parser.expressions.first(), parser.expressions.last()) {
+            (Some(first), Some(last)) => LexicalSpan::new(first.span().start(), last.span().end()),
+            _ => LexicalSpan::zeros(),
+        };
         Self {
             expression: Expression::list(parser.expressions).with_span(span),
             errors: parser.errors,
@@ -53,9 +54,13 @@ pub struct Parser {
 enum -> TEXT list
 atom -> NUMBER
       | TEXT
+      | QUOTED
 
 presence -> atom
-bind -> TEXT "=" expression
+name -> TEXT
+      | NUMBER
+      | QUOTED
+bind -> name "=" expression
 list -> "(" expression* ")"
 expression -> presence
             | bind
@@ -132,14 +137,34 @@ impl Parser {
             return Ok(token)
         }
 
-        Err(self.unexpected_token_error(token, &[Tk::Text, Tk::Number]))
+        if token.kind() == Tk::Quoted {
+            return Ok(token)
+        }
+
+        Err(self.unexpected_token_error(token, &[Tk::Text, Tk::Number, Tk::Quoted]))
+    }
+
+    fn unquote(&self, token: &Token) -> Result<String, ParserError> {
+        match unquote(token.lexeme()) {
+            Ok(text) => Ok(text),
+            Err(QuoteError::Unterminated) => Err(ParserError::on_span(
+                Ek::UnterminatedString(token.clone()), token.span(), &self.source
+            )),
+            Err(QuoteError::InvalidUnicodeEscape) => Err(ParserError::on_span(
+                Ek::InvalidEscape(token.clone()), token.span(), &self.source
+            )),
+        }
     }
 
     fn parse_binding(&mut self, identifier: Token) -> Result<Expression, ParserError> {
-        if let Some(errant_index) = identifier.invalid_identifier_char_index() {
-            let errant_index = identifier.span().start() + errant_index;
+        let name = if identifier.kind() == Tk::Quoted {
+            self.unquote(&identifier)?
+        } else if identifier.is_bare_name() {
+            identifier.lexeme().to_string()
+        } else {
+            let errant_index = identifier.span().start() + identifier.invalid_identifier_char_index().unwrap_or(0);
             return Err(ParserError::on_span(Ek::InvalidIdentifier(identifier), LexicalSpan::new(errant_index, errant_index + 1), &self.source))
-        }
+        };
 
         if let Err(token) = self.eat(Tk::Equals) {
             let token = token.eoi_check(&self.source)?;
@@ -158,11 +183,11 @@ impl Parser {
             list.prepend_into_list(value);
 
             let span = identifier.span().combine(list.span());
-            return Ok(Expression::binding(identifier.lexeme(), list).with_span(span));
+            return Ok(Expression::binding(name, list).with_span(span));
         }
 
         let span = identifier.span().combine(value.span());
-        Ok(Expression::binding(identifier.lexeme(), value).with_span(span))
+        Ok(Expression::binding(name, value).with_span(span))
     }
 
     fn parse_list(&mut self, l_paren: Token) -> Result<Expression, ParserError> {
@@ -196,9 +221,9 @@ impl Parser {
         }
 
         let atom = match name.kind() {
-            Kind::Number => Atom::Number(name.lexeme().to_string()),
-            Kind::Text => Atom::Text(name.lexeme().to_string()),
-            _ => return Err(self.unexpected_token_error(name, &[Tk::Text, Tk::Number])),
+            Kind::Number | Kind::Text => Atom::from_token(name.lexeme()),
+            Kind::Quoted => Atom::Quoted(self.unquote(&name)?),
+            _ => return Err(self.unexpected_token_error(name, &[Tk::Text, Tk::Number, Tk::Quoted])),
         };
 
         Ok(Expression::presence(atom).with_span(name.span()))
@@ -219,6 +244,7 @@ impl Parser {
 
 #[cfg(test)]
 mod tests {
+    use crate::expression::ExpressionData;
     use crate::lexical_span::LexicalSpan;
     use super::*;
 
@@ -306,6 +332,29 @@ mod tests {
         assert_eq!(p.get_first_expr_or_panic(), binding);
     }
 
+    #[test]
+    fn empty_input() {
+        let p = Parser::new("# nothing but a comment").parse().unwrap();
+        assert_eq!(p, Expression::list(vec![]));
+    }
+
+    #[test]
+    fn integer_and_quoted_names() {
+        let p = Parser::new("8080 = a -1 = b \"x y\" = c").parse().unwrap();
+        let names = p.into_iter().map(|x| match x.data {
+            ExpressionData::BindingExpr(b) => b.name,
+            other => panic!("expected a binding, but got {:?}", other),
+        }).collect_vec();
+        assert_eq!(names, vec!["8080", "-1", "x y"]);
+    }
+
+    #[test]
+    fn invalid_names() {
+        for source in ["1.5 = a", "a.b = c", "true_ = x y.z = 1"] {
+            assert!(!Parser::new(source).parse().errors().is_empty(), "{}", source);
+        }
+    }
+
     #[test]
     #[should_panic]
     fn unclosed_list() {
@@ -12,6 +12,8 @@ pub enum Kind {
     UnexpectedToken(Token, &'static [Tk]),
     ReachedEoi,
     InvalidIdentifier(Token),
+    UnterminatedString(Token),
+    InvalidEscape(Token),
 }
 
 fn unexpected_text<G: ToString + ?Sized, E: ToString>(got: &G, expected: &[E]) -> String {
@@ -31,6 +33,8 @@ impl Describe for Kind {
             ),
             Kind::ReachedEoi => String::from("Reached end of input while parsing!"),
             Kind::InvalidIdentifier(token) => format!("Invalid identifier '{}'.", token.lexeme()),
+            Kind::UnterminatedString(_) => String::from("Quoted string is missing its closing '\"'."),
+            Kind::InvalidEscape(token) => format!("Invalid unicode escape in the string {}.", token.lexeme()),
         }
     }
 }
@@ -0,0 +1,473 @@
+use std::io::{self, Write};
+use std::mem;
+use crate::expression::Expression;
+use crate::expression::ExpressionData::{BindingExpr, List, Presence};
+
+/// The number of columns a tab is assumed to take up when measuring line width.
+pub const TAB_WIDTH: usize = 4;
+
+#[derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash)]
+pub enum IndentStyle {
+    Tabs,
+    Spaces(usize),
+}
+
+impl IndentStyle {
+    fn width(&self) -> usize {
+        match self {
+            IndentStyle::Tabs => TAB_WIDTH,
+            IndentStyle::Spaces(n) => *n,
+        }
+    }
+
+    fn unit(&self) -> String {
+        match self {
+            IndentStyle::Tabs => "\t".to_string(),
+            IndentStyle::Spaces(n) => " ".repeat(*n),
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash)]
+pub enum CommentPlacement {
+    /// Comments are written on their own lines directly above the expression they describe.
+    Above,
+    /// Single line comments are written at the end of the first line of the expression they
+    /// describe. Comments spanning several lines are still written above.
+    Trailing,
+}
+
+/// Controls the layout produced by `Expression::pretty_with`. The default options produce the
+/// same layout as `Expression::pretty`.
+#[derive(Debug, Clone, Ord, PartialOrd, Eq, PartialEq, Hash)]
+pub struct PrettyOptions {
+    pub indent: IndentStyle,
+    /// When set, lists which fit on the current line without exceeding this many columns are
+    /// written inline as `(a b c)`. When unset every list is broken over multiple lines.
+    pub max_width: Option<usize>,
+    /// Pad the names of consecutive sibling bindings so their `=` signs line up.
+    pub align_equals: bool,
+    /// Separate the entries of the outermost list with a blank line.
+    pub blank_lines_between_sections: bool,
+    pub comment_placement: CommentPlacement,
+}
+
+impl Default for PrettyOptions {
+    fn default() -> Self {
+        Self {
+            indent: IndentStyle::Tabs,
+            max_width: None,
+            align_equals: false,
+            blank_lines_between_sections: false,
+            comment_placement: CommentPlacement::Above,
+        }
+    }
+}
+
+impl PrettyOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_indent(mut self, indent: IndentStyle) -> Self {
+        self.indent = indent;
+        self
+    }
+
+    pub fn with_max_width(mut self, max_width: usize) -> Self {
+        self.max_width = Some(max_width);
+        self
+    }
+
+    pub fn with_aligned_equals(mut self, align_equals: bool) -> Self {
+        self.align_equals = align_equals;
+        self
+    }
+
+    pub fn with_blank_lines_between_sections(mut self, blank_lines: bool) -> Self {
+        self.blank_lines_between_sections = blank_lines;
+        self
+    }
+
+    pub fn with_comment_placement(mut self, placement: CommentPlacement) -> Self {
+        self.comment_placement = placement;
+        self
+    }
+}
+
+// The comment lines belonging to an expression. A binding owns the comment of its value too,
+// since there is nowhere sensible to put a comment between the `=` and the value.
+fn comment_lines(expr: &Expression) -> Vec<&str> {
+    let mut lines: Vec<&str> = expr.comment.iter().flat_map(|x| x.lines()).collect();
+    if let BindingExpr(b) = &expr.data {
+        lines.extend(b.value.comment.iter().flat_map(|x| x.lines()));
+    }
+    lines
+}
+
+// The width of `expr` when written on a single line, or `None` if that is wider than `budget` or
+// impossible because the expression holds a comment. Stops as soon as `budget` is exceeded, so
+// checking whether a list fits never walks more than `budget` nodes.
+fn inline_width(expr: &Expression, budget: usize) -> Option<usize> {
+    if expr.comment.is_some() {
+        return None
+    }
+
+    let width = match &expr.data {
+        Presence(atom, _) => atom.to_string().chars().count(),
+        BindingExpr(b) => {
+            let name = b.written_name().chars().count() + " = ".len();
+            name + inline_width(&b.value, budget.checked_sub(name)?)?
+        }
+        List(l, _) => {
+            let mut width = "()".len() + l.len().saturating_sub(1);
+            for element in l {
+                width += inline_width(element, budget.checked_sub(width)?)?;
+            }
+            width
+        }
+    };
+
+    (width <= budget).then_some(width)
+}
+
+/// Writes text to an `io::Write` while keeping track of the current column. The first IO error is
+/// latched and every write after it is skipped, so the layout code does not have to thread
+/// `Result`s through every call. The error is reported by `finish`.
+pub(crate) struct Printer<'a, W: Write> {
+    pub(crate) options: &'a PrettyOptions,
+    out: W,
+    indent_unit: String,
+    column: usize,
+    written: usize,
+    error: Option<io::Error>,
+}
+
+impl<'a, W: Write> Printer<'a, W> {
+    pub(crate) fn new(options: &'a PrettyOptions, out: W) -> Self {
+        Self {
+            options,
+            out,
+            indent_unit: options.indent.unit(),
+            column: 0,
+            written: 0,
+            error: None,
+        }
+    }
+
+    pub(crate) fn column(&self) -> usize {
+        self.column
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.written == 0
+    }
+
+    fn write_raw(&mut self, text: &str) {
+        if self.error.is_some() {
+            return
+        }
+
+        match self.out.write_all(text.as_bytes()) {
+            Ok(()) => self.written += text.len(),
+            Err(e) => self.error = Some(e),
+        }
+    }
+
+    pub(crate) fn push(&mut self, text: &str) {
+        self.write_raw(text);
+        match text.rfind('\n') {
+            Some(i) => self.column = text[i + 1..].chars().count(),
+            None => self.column += text.chars().count(),
+        }
+    }
+
+    /// End the current line without indenting the next one.
+    pub(crate) fn line_break(&mut self) {
+        self.write_raw("\n");
+        self.column = 0;
+    }
+
+    /// End the current line and indent the next one to `depth`.
+    pub(crate) fn newline(&mut self, depth: usize) {
+        self.line_break();
+        for _ in 0..depth {
+            let unit = mem::take(&mut self.indent_unit);
+            self.write_raw(&unit);
+            self.indent_unit = unit;
+        }
+        self.column = depth * self.options.indent.width();
+    }
+
+    pub(crate) fn finish(self) -> io::Result<W> {
+        match self.error {
+            Some(e) => Err(e),
+            None => Ok(self.out),
+        }
+    }
+
+    fn fits_inline(&self, expr: &Expression, trailing: Option<&str>) -> bool {
+        let Some(max_width) = self.options.max_width else { return false };
+        let trailing = trailing.map(|x| x.chars().count() + " # ".len()).unwrap_or(0);
+        let Some(budget) = max_width.checked_sub(self.column + trailing) else { return false };
+        inline_width(expr, budget).is_some()
+    }
+
+    fn push_trailing(&mut self, trailing: Option<&str>) {
+        if let Some(comment) = trailing {
+            self.push(" # ");
+            self.push(comment);
+        }
+    }
+
+    fn expression(&mut self, expr: &Expression, depth: usize, name_width: usize) {
+        let comments = comment_lines(expr);
+        let trailing = match (self.options.comment_placement, comments.as_slice()) {
+            (CommentPlacement::Trailing, [line]) => Some(*line),
+            _ => {
+                for line in &comments {
+                    self.push("# ");
+                    self.push(line);
+                    self.newline(depth);
+                }
+                None
+            }
+        };
+
+        match &expr.data {
+            BindingExpr(b) => {
+                self.push(&b.written_name());
+                let padding = name_width.saturating_sub(b.written_name().chars().count());
+                self.push(&" ".repeat(padding));
+                self.push(" = ");
+                self.value(&b.value, depth, trailing);
+            }
+            _ => self.value(expr, depth, trailing),
+        }
+    }
+
+    // Write an expression whose comments have already been handled by the caller.
+    fn value(&mut self, expr: &Expression, depth: usize, trailing: Option<&str>) {
+        match &expr.data {
+            Presence(atom, _) => {
+                self.push(&atom.to_string());
+                self.push_trailing(trailing);
+            }
+            BindingExpr(b) => {
+                self.push(&b.written_name());
+                self.push(" = ");
+                self.value(&b.value, depth, trailing);
+            }
+            List(l, _) if l.is_empty() => {
+                self.push("()");
+                self.push_trailing(trailing);
+            }
+            List(l, _) if self.fits_inline(expr, trailing) => {
+                self.push("(");
+                for (index, element) in l.iter().enumerate() {
+                    if index > 0 {
+                        self.push(" ");
+                    }
+                    self.value(element, depth, None);
+                }
+                self.push(")");
+                self.push_trailing(trailing);
+            }
+            List(l, _) => {
+                self.push("(");
+                self.push_trailing(trailing);
+
+                let name_widths = self.name_widths(l);
+                for (index, (element, name_width)) in l.iter().zip(name_widths).enumerate() {
+                    if index > 0 && depth == 0 && self.options.blank_lines_between_sections {
+                        self.line_break();
+                    }
+                    self.newline(depth + 1);
+                    self.expression(element, depth + 1, name_width);
+                }
+
+                self.newline(depth);
+                self.push(")");
+            }
+        }
+    }
+
+    // The width each element's binding name should be padded to. Consecutive bindings are padded
+    // to the longest name among them.
+    fn name_widths(&self, elements: &[Expression]) -> Vec<usize> {
+        let mut widths = vec![0; elements.len()];
+        if !self.options.align_equals {
+            return widths
+        }
+
+        let mut run_start = 0;
+        for index in 0..=elements.len() {
+            let name_len = elements.get(index).and_then(|x| match &x.data {
+                BindingExpr(b) => Some(b.written_name().chars().count()),
+                _ => None,
+            });
+
+            if name_len.is_none() {
+                let run = &mut widths[run_start..index];
+                let longest = elements[run_start..index]
+                    .iter()
+                    .filter_map(|x| match &x.data {
+                        BindingExpr(b) => Some(b.written_name().chars().count()),
+                        _ => None,
+                    })
+                    .max()
+                    .unwrap_or(0);
+                run.fill(longest);
+                run_start = index + 1;
+            }
+        }
+
+        widths
+    }
+}
+
+impl Expression {
+    /// Pretty print this expression with the layout described by `options`.
+    pub fn pretty_with(&self, options: &PrettyOptions) -> String {
+        let mut out = Vec::new();
+        self.write_pretty_with(&mut out, options)
+            .expect("writing to a Vec can not fail");
+        String::from_utf8(out).expect("the pretty printer only writes UTF-8")
+    }
+
+    /// Pretty print this expression into `writer` in a single pass, without building the output
+    /// in memory first.
+    pub fn write_pretty(&self, writer: &mut impl Write) -> io::Result<()> {
+        self.write_pretty_with(writer, &PrettyOptions::default())
+    }
+
+    /// Pretty print this expression into `writer` with the layout described by `options`.
+    pub fn write_pretty_with(&self, writer: &mut impl Write, options: &PrettyOptions) -> io::Result<()> {
+        let mut printer = Printer::new(options, writer);
+        printer.expression(self, 0, 0);
+        printer.finish().map(|_| ())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn server() -> Expression {
+        Expression::list(vec![
+            Expression::binding("address", Expression::presence("localhost")),
+            Expression::binding("port", Expression::presence(8080)),
+            Expression::binding("names", Expression::list(vec![
+                Expression::presence("a"),
+                Expression::presence("b"),
+            ])),
+        ])
+    }
+
+    #[test]
+    fn default_matches_the_original_layout() {
+        // The text `Expression::pretty` wrote before it took options.
+        assert_eq!(
+            server().pretty_with(&PrettyOptions::default()),
+            "(\n\taddress = localhost\n\tport = 8080\n\tnames = (\n\t\ta\n\t\tb\n\t)\n)"
+        );
+    }
+
+    #[test]
+    fn spaces() {
+        let options = PrettyOptions::new().with_indent(IndentStyle::Spaces(2));
+        assert_eq!(
+            server().pretty_with(&options),
+            "(\n  address = localhost\n  port = 8080\n  names = (\n    a\n    b\n  )\n)"
+        );
+    }
+
+    #[test]
+    fn short_lists_inline() {
+        let options = PrettyOptions::new().with_max_width(20);
+        assert_eq!(
+            server().pretty_with(&options),
+            "(\n\taddress = localhost\n\tport = 8080\n\tnames = (a b)\n)"
+        );
+    }
+
+    #[test]
+    fn whole_expression_inline() {
+        let options = PrettyOptions::new().with_max_width(80);
+        assert_eq!(server().pretty_with(&options), server().dump());
+    }
+
+    #[test]
+    fn width_is_respected() {
+        let options = PrettyOptions::new().with_max_width(16);
+        assert_eq!(
+            server().pretty_with(&options),
+            "(\n\taddress = localhost\n\tport = 8080\n\tnames = (\n\t\ta\n\t\tb\n\t)\n)"
+        );
+    }
+
+    #[test]
+    fn align_equals() {
+        let options = PrettyOptions::new().with_aligned_equals(true);
+        let expr = Expression::list(vec![
+            Expression::presence("Server"),
+            Expression::binding("address", Expression::presence("localhost")),
+            Expression::binding("port", Expression::presence(8080)),
+            Expression::presence("verbose"),
+            Expression::binding("x", Expression::presence(1)),
+        ]);
+        assert_eq!(
+            expr.pretty_with(&options),
+            "(\n\tServer\n\taddress = localhost\n\tport    = 8080\n\tverbose\n\tx = 1\n)"
+        );
+    }
+
+    #[test]
+    fn blank_lines_between_sections() {
+        let options = PrettyOptions::new().with_blank_lines_between_sections(true);
+        assert_eq!(
+            server().pretty_with(&options),
+            "(\n\taddress = localhost\n\n\tport = 8080\n\n\tnames = (\n\t\ta\n\t\tb\n\t)\n)"
+        );
+    }
+
+    #[test]
+    fn comments_above() {
+        let expr = Expression::list(vec![
+            Expression::binding("port", Expression::presence(8080)).with_comment("The port."),
+        ]);
+        assert_eq!(expr.pretty_with(&PrettyOptions::default()), "(\n\t# The port.\n\tport = 8080\n)");
+    }
+
+    #[test]
+    fn comments_trailing() {
+        let options = PrettyOptions::new().with_comment_placement(CommentPlacement::Trailing);
+        let expr = Expression::list(vec![
+            Expression::binding("port", Expression::presence(8080)).with_comment("The port."),
+            Expression::binding("names", Expression::list(vec![Expression::presence("a")]))
+                .with_comment("Some names."),
+            Expression::binding("x", Expression::presence(1)).with_comment("Two\nlines"),
+        ]);
+        assert_eq!(
+            expr.pretty_with(&options),
+            "(\n\tport = 8080 # The port.\n\tnames = ( # Some names.\n\t\ta\n\t)\n\t# Two\n\t# lines\n\tx = 1\n)"
+        );
+    }
+
+    #[test]
+    fn comments_prevent_inlining() {
+        let options = PrettyOptions::new().with_max_width(80);
+        let expr = Expression::list(vec![
+            Expression::presence("a").with_comment("first"),
+            Expression::presence("b"),
+        ]);
+        assert_eq!(expr.pretty_with(&options), "(\n\t# first\n\ta\n\tb\n)");
+    }
+
+    #[test]
+    fn value_comments_belong_to_the_binding() {
+        let options = PrettyOptions::new().with_comment_placement(CommentPlacement::Trailing);
+        let expr = Expression::binding("port", Expression::presence(8080).with_comment("The port."));
+        assert_eq!(expr.pretty_with(&options), "port = 8080 # The port.");
+    }
+}
@@ -0,0 +1,362 @@
+use std::any;
+use ::serde::de::{self, DeserializeSeed, IntoDeserializer, Visitor};
+use ::serde::forward_to_deserialize_any;
+use crate::atom::Atom;
+use crate::expression::{Expression, ExpressionData};
+use crate::expression_iterator::ExpressionIterator;
+use crate::lexical_span::LexicalSpan;
+use crate::serialization::map_key::MapKey;
+use crate::serialization::EasyConfig;
+use crate::serialization::serialization_error::{Kind, SerializationError};
+use crate::value::Value;
+
+type Result<T> = std::result::Result<T, SerializationError>;
+
+// Mirrors the two ways `EasyConfig::deserialize` is handed its input. Most values are deserialized
+// from an iterator over their contents, but enums take their whole expression from the iterator
+// of whatever holds them, which is what `EasyConfig::PASSTHROUGH` is for. An element of a list
+// or the value of a binding is kept whole until we know which of the two it needs.
+enum Input {
+    Contents(ExpressionIterator),
+    Element(Expression),
+}
+
+impl Input {
+    fn contents(self) -> ExpressionIterator {
+        match self {
+            Input::Contents(exprs) => exprs,
+            Input::Element(expr) => expr.into_iter(),
+        }
+    }
+
+    fn passthrough(self) -> ExpressionIterator {
+        match self {
+            Input::Contents(exprs) => exprs,
+            Input::Element(expr) => ExpressionIterator::new(Expression::list(vec![expr])),
+        }
+    }
+}
+
+/// Deserializes from an `ExpressionIterator`, reading the same layout `#[derive(EasyConfig)]`
+/// writes.
+pub struct Deserializer<'a> {
+    input: Input,
+    source_text: &'a str,
+}
+
+impl<'a> Deserializer<'a> {
+    /// `exprs` is the same iterator `EasyConfig::deserialize` would be given.
+    pub fn new(exprs: ExpressionIterator, source_text: &'a str) -> Self {
+        Self { input: Input::Contents(exprs), source_text }
+    }
+
+    fn element(expr: Expression, source_text: &'a str) -> Self {
+        Self { input: Input::Element(expr), source_text }
+    }
+
+    // Deserialize through an existing `EasyConfig` impl which reads a single presence.
+    fn primitive<T: EasyConfig>(self) -> Result<T> {
+        T::deserialize(&mut self.input.passthrough(), self.source_text)
+    }
+}
+
+macro_rules! deserialize_primitive {
+    ($method:ident, $ty:ty, $visit:ident) => {
+        fn $method<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+            visitor.$visit(self.primitive::<$ty>()?)
+        }
+    };
+}
+
+impl<'de> de::Deserializer<'de> for Deserializer<'_> {
+    type Error = SerializationError;
+
+    // Without a type to guide it, the input is read as a `Value`.
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        let value = Value::deserialize(&mut self.input.contents(), self.source_text)?;
+        de::Deserializer::deserialize_any(value, visitor)
+    }
+
+    deserialize_primitive!(deserialize_bool, bool, visit_bool);
+    deserialize_primitive!(deserialize_i8, i8, visit_i8);
+    deserialize_primitive!(deserialize_i16, i16, visit_i16);
+    deserialize_primitive!(deserialize_i32, i32, visit_i32);
+    deserialize_primitive!(deserialize_i64, i64, visit_i64);
+    deserialize_primitive!(deserialize_i128, i128, visit_i128);
+    deserialize_primitive!(deserialize_u8, u8, visit_u8);
+    deserialize_primitive!(deserialize_u16, u16, visit_u16);
+    deserialize_primitive!(deserialize_u32, u32, visit_u32);
+    deserialize_primitive!(deserialize_u64, u64, visit_u64);
+    deserialize_primitive!(deserialize_u128, u128, visit_u128);
+    deserialize_primitive!(deserialize_f32, f32, visit_f32);
+    deserialize_primitive!(deserialize_f64, f64, visit_f64);
+    deserialize_primitive!(deserialize_char, char, visit_char);
+    deserialize_primitive!(deserialize_str, String, visit_string);
+    deserialize_primitive!(deserialize_string, String, visit_string);
+    deserialize_primitive!(deserialize_identifier, String, visit_string);
+
+    fn deserialize_bytes<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        self.deserialize_byte_buf(visitor)
+    }
+    fn deserialize_byte_buf<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        visitor.visit_byte_buf(Vec::<u8>::deserialize(&mut self.input.contents(), self.source_text)?)
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        let source_text = self.source_text;
+        let mut exprs = self.input.contents();
+        let name = exprs.next_or_err(source_text)?;
+        let name_span = name.span();
+
+        let ExpressionData::Presence(Atom::Text(discriminant), _) = &name.data else {
+            return Err(SerializationError::on_span(Kind::ExpectedPresence(name), name_span, source_text))
+        };
+
+        match discriminant.to_lowercase().as_str() {
+            "none" => visitor.visit_none(),
+            "some" => {
+                let args = exprs.next_list_or_err(source_text)?;
+                visitor.visit_some(Deserializer::new(args.into_iter(), source_text))
+            }
+            _ => Err(SerializationError::on_span(
+                Kind::ExpectedDiscriminant(discriminant.clone(), &["Some", "None"]),
+                name_span,
+                source_text
+            )),
+        }
+    }
+
+    fn deserialize_unit<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        visitor.visit_unit()
+    }
+    fn deserialize_unit_struct<V: Visitor<'de>>(self, name: &'static str, visitor: V) -> Result<V::Value> {
+        self.input.contents().eat_presence_if_present_and_matching(name);
+        visitor.visit_unit()
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(self, name: &'static str, visitor: V) -> Result<V::Value> {
+        let mut exprs = self.input.contents();
+        exprs.eat_presence_if_present_and_matching(name);
+        let value = exprs.next_or_err(self.source_text)?;
+        visitor.visit_newtype_struct(Deserializer::element(value, self.source_text))
+    }
+
+    fn deserialize_seq<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        visitor.visit_seq(Elements { exprs: self.input.contents(), source_text: self.source_text })
+    }
+    fn deserialize_tuple<V: Visitor<'de>>(self, _len: usize, visitor: V) -> Result<V::Value> {
+        self.deserialize_seq(visitor)
+    }
+    fn deserialize_tuple_struct<V: Visitor<'de>>(self, name: &'static str, _len: usize, visitor: V) -> Result<V::Value> {
+        let mut exprs = self.input.contents();
+        exprs.eat_presence_if_present_and_matching(name);
+        visitor.visit_seq(Elements { exprs, source_text: self.source_text })
+    }
+
+    fn deserialize_map<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        visitor.visit_map(Bindings { exprs: self.input.contents(), value: None, source_text: self.source_text })
+    }
+    fn deserialize_struct<V: Visitor<'de>>(self, name: &'static str, _fields: &'static [&'static str], visitor: V) -> Result<V::Value> {
+        let mut exprs = self.input.contents();
+        exprs.eat_presence_if_present_and_matching(name);
+        visitor.visit_map(Bindings { exprs, value: None, source_text: self.source_text })
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(self, _name: &'static str, _variants: &'static [&'static str], visitor: V) -> Result<V::Value> {
+        let (variant, fields) = self.input.passthrough().extract_enum(self.source_text)?;
+        visitor.visit_enum(Variant { variant, fields, source_text: self.source_text })
+    }
+
+    fn deserialize_ignored_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        visitor.visit_unit()
+    }
+}
+
+struct Elements<'a> {
+    exprs: ExpressionIterator,
+    source_text: &'a str,
+}
+
+impl<'de> de::SeqAccess<'de> for Elements<'_> {
+    type Error = SerializationError;
+
+    fn next_element_seed<T: DeserializeSeed<'de>>(&mut self, seed: T) -> Result<Option<T::Value>> {
+        match self.exprs.next() {
+            None => Ok(None),
+            Some(expr) => seed.deserialize(Deserializer::element(expr, self.source_text)).map(Some),
+        }
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.exprs.len())
+    }
+}
+
+struct Bindings<'a> {
+    exprs: ExpressionIterator,
+    value: Option<Expression>,
+    source_text: &'a str,
+}
+
+impl<'de> de::MapAccess<'de> for Bindings<'_> {
+    type Error = SerializationError;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(&mut self, seed: K) -> Result<Option<K::Value>> {
+        let Some(expr) = self.exprs.next() else { return Ok(None) };
+        let span = expr.span();
+        let ExpressionData::BindingExpr(binding) = expr.data else {
+            return Err(SerializationError::on_span(Kind::ExpectedBinding(expr), span, self.source_text))
+        };
+
+        self.value = Some(*binding.value);
+        seed.deserialize(Key { name: binding.name, span, source_text: self.source_text }).map(Some)
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(&mut self, seed: V) -> Result<V::Value> {
+        let value = self.value.take().expect("next_value_seed is always called after next_key_seed");
+        seed.deserialize(Deserializer::element(value, self.source_text))
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.exprs.len())
+    }
+}
+
+// A binding name read as the key of a map. Keys are strings unless the map asks for a number,
+// bool or char, which are parsed from the name the same way `MapKey` parses them.
+struct Key<'a> {
+    name: String,
+    span: LexicalSpan,
+    source_text: &'a str,
+}
+
+impl Key<'_> {
+    fn parse<T: MapKey>(self) -> Result<T> {
+        T::from_key(&self.name).ok_or_else(|| SerializationError::on_span(
+            Kind::InvalidMapKey { key: self.name, target: any::type_name::<T>() },
+            self.span,
+            self.source_text
+        ))
+    }
+}
+
+macro_rules! deserialize_key {
+    ($method:ident, $ty:ty, $visit:ident) => {
+        fn $method<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+            visitor.$visit(self.parse::<$ty>()?)
+        }
+    };
+}
+
+impl<'de> de::Deserializer<'de> for Key<'_> {
+    type Error = SerializationError;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        visitor.visit_string(self.name)
+    }
+
+    deserialize_key!(deserialize_bool, bool, visit_bool);
+    deserialize_key!(deserialize_i8, i8, visit_i8);
+    deserialize_key!(deserialize_i16, i16, visit_i16);
+    deserialize_key!(deserialize_i32, i32, visit_i32);
+    deserialize_key!(deserialize_i64, i64, visit_i64);
+    deserialize_key!(deserialize_i128, i128, visit_i128);
+    deserialize_key!(deserialize_u8, u8, visit_u8);
+    deserialize_key!(deserialize_u16, u16, visit_u16);
+    deserialize_key!(deserialize_u32, u32, visit_u32);
+    deserialize_key!(deserialize_u64, u64, visit_u64);
+    deserialize_key!(deserialize_u128, u128, visit_u128);
+    deserialize_key!(deserialize_char, char, visit_char);
+
+    forward_to_deserialize_any! {
+        f32 f64 str string bytes byte_buf option unit unit_struct newtype_struct seq tuple
+        tuple_struct map struct enum identifier ignored_any
+    }
+}
+
+struct Variant<'a> {
+    variant: String,
+    fields: Expression,
+    source_text: &'a str,
+}
+
+impl<'de, 'a> de::EnumAccess<'de> for Variant<'a> {
+    type Error = SerializationError;
+    type Variant = Self;
+
+    fn variant_seed<V: DeserializeSeed<'de>>(self, seed: V) -> Result<(V::Value, Self)> {
+        let name: de::value::StringDeserializer<SerializationError> = self.variant.clone().into_deserializer();
+        let variant = seed.deserialize(name)?;
+        Ok((variant, self))
+    }
+}
+
+impl<'de> de::VariantAccess<'de> for Variant<'_> {
+    type Error = SerializationError;
+
+    fn unit_variant(self) -> Result<()> {
+        Ok(())
+    }
+
+    fn newtype_variant_seed<T: DeserializeSeed<'de>>(self, seed: T) -> Result<T::Value> {
+        let value = self.fields.into_iter().next_or_err(self.source_text)?;
+        seed.deserialize(Deserializer::element(value, self.source_text))
+    }
+
+    fn tuple_variant<V: Visitor<'de>>(self, _len: usize, visitor: V) -> Result<V::Value> {
+        visitor.visit_seq(Elements { exprs: self.fields.into_iter(), source_text: self.source_text })
+    }
+
+    fn struct_variant<V: Visitor<'de>>(self, _fields: &'static [&'static str], visitor: V) -> Result<V::Value> {
+        visitor.visit_map(Bindings { exprs: self.fields.into_iter(), value: None, source_text: self.source_text })
+    }
+}
+
+impl<'de> de::Deserializer<'de> for Value {
+    type Error = SerializationError;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        match self {
+            Value::Map(map) => visitor.visit_map(de::value::MapDeserializer::new(map.into_iter())),
+            Value::List(list) => visitor.visit_seq(de::value::SeqDeserializer::new(list.into_iter())),
+            Value::String(s) => visitor.visit_string(s),
+            Value::Integer(i) => {
+                if let Some(v) = i.convert::<i64>() {
+                    visitor.visit_i64(v)
+                } else if let Some(v) = i.convert::<u64>() {
+                    visitor.visit_u64(v)
+                } else if let Some(v) = i.convert::<i128>() {
+                    visitor.visit_i128(v)
+                } else if let Some(v) = i.convert::<u128>() {
+                    visitor.visit_u128(v)
+                } else {
+                    Err(SerializationError::FirstLevelError(
+                        Kind::IntegerOutOfRange {
+                            literal: i.to_string(),
+                            target: "i128",
+                            range: format!("{} to {}", i128::MIN, i128::MAX),
+                        },
+                        String::new()
+                    ))
+                }
+            }
+            Value::Float(f) => visitor.visit_f64(f),
+            Value::Bool(b) => visitor.visit_bool(b),
+            Value::Flag => visitor.visit_unit(),
+        }
+    }
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf option unit unit_struct newtype_struct seq tuple
+        tuple_struct map struct enum identifier ignored_any
+    }
+}
+
+impl IntoDeserializer<'_, SerializationError> for Value {
+    type Deserializer = Value;
+
+    fn into_deserializer(self) -> Value {
+        self
+    }
+}
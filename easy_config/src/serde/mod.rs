@@ -0,0 +1,78 @@
+//! A bridge between serde and easy_config, for types which implement serde's traits but can't
+//! derive `EasyConfig`. Values are laid out exactly as `#[derive(EasyConfig)]` would lay them out,
+//! so a file can be read through either path.
+//!
+//! ```ignore
+//! #[derive(serde::Serialize, serde::Deserialize)]
+//! struct Server { address: String, port: u16 }
+//!
+//! let server: Server = easy_config::serde::from_str("address = localhost port = 8080")?;
+//! let text = easy_config::serde::to_string_pretty(&server)?;
+//! ```
+
+mod ser;
+mod de;
+
+use std::fmt::Display;
+use crate::expression::{Expression, ExpressionData};
+use crate::expression_iterator::ExpressionIterator;
+use crate::parser::Parser;
+use crate::serialization::serialization_error::{Kind, SerializationError};
+
+pub use ser::Serializer;
+pub use de::Deserializer;
+
+impl ::serde::ser::Error for SerializationError {
+    fn custom<T: Display>(msg: T) -> Self {
+        SerializationError::FirstLevelError(Kind::Custom(msg.to_string()), String::new())
+    }
+}
+
+impl ::serde::de::Error for SerializationError {
+    fn custom<T: Display>(msg: T) -> Self {
+        SerializationError::FirstLevelError(Kind::Custom(msg.to_string()), String::new())
+    }
+
+    fn unknown_variant(variant: &str, expected: &'static [&'static str]) -> Self {
+        SerializationError::FirstLevelError(Kind::ExpectedDiscriminant(variant.to_string(), expected), String::new())
+    }
+
+    fn missing_field(field: &'static str) -> Self {
+        SerializationError::FirstLevelError(Kind::MissingField(field.to_string()), String::new())
+    }
+}
+
+/// Serialize `value` into an `Expression`.
+pub fn to_expression<T: ::serde::Serialize + ?Sized>(value: &T) -> Result<Expression, SerializationError> {
+    value.serialize(Serializer)
+}
+
+/// Serialize `value` and pretty print it.
+pub fn to_string_pretty<T: ::serde::Serialize + ?Sized>(value: &T) -> Result<String, SerializationError> {
+    Ok(to_expression(value)?.pretty())
+}
+
+/// Deserialize a `T` from `exprs`, which were parsed from `source_text`. `exprs` is consumed the
+/// same way `EasyConfig::deserialize` would consume it.
+pub fn from_expressions<T: ::serde::de::DeserializeOwned>(exprs: ExpressionIterator, source_text: impl AsRef<str>) -> Result<T, SerializationError> {
+    T::deserialize(Deserializer::new(exprs, source_text.as_ref()))
+}
+
+/// Parse `source_text` and deserialize a `T` from it. A file holding a single list, like the output
+/// of `to_string_pretty`, is read as the contents of that list.
+pub fn from_str<T: ::serde::de::DeserializeOwned>(source_text: impl AsRef<str>) -> Result<T, SerializationError> {
+    let source_text = source_text.as_ref();
+    let finished_parser = Parser::new(source_text).parse();
+    if !finished_parser.errors().is_empty() {
+        return Err(SerializationError::FirstLevelError(Kind::ParserErrors(finished_parser.errors().clone()), String::new()));
+    }
+
+    let file = finished_parser.unwrap();
+    let exprs = match &file.data {
+        ExpressionData::List(elements, _) if elements.len() == 1 && elements[0].is_list() => {
+            file.into_iter().next().unwrap().into_iter()
+        },
+        _ => file.into_iter(),
+    };
+    from_expressions(exprs, source_text)
+}
@@ -0,0 +1,383 @@
+use ::serde::ser::{self, Serialize};
+use crate::expression::Expression;
+use crate::serialization::EasyConfig;
+use crate::serialization::serialization_error::SerializationError;
+
+/// Serializes into an `Expression`, using the same layout as `#[derive(EasyConfig)]`.
+#[derive(Debug, Copy, Clone, Default)]
+pub struct Serializer;
+
+type Result<T> = std::result::Result<T, SerializationError>;
+
+// `name` followed by `rest`, which is how the derive writes structs.
+fn named(name: &str, rest: Vec<Expression>) -> Expression {
+    let mut list = Expression::list(rest);
+    list.prepend_into_list(Expression::presence(name));
+    list
+}
+
+// `variant` followed by a list of its fields, which is how the derive writes enum variants.
+fn variant(variant: &str, fields: Vec<Expression>) -> Expression {
+    Expression::list(vec![Expression::presence(variant), Expression::list(fields)])
+}
+
+impl ser::Serializer for Serializer {
+    type Ok = Expression;
+    type Error = SerializationError;
+
+    type SerializeSeq = SerializeList;
+    type SerializeTuple = SerializeList;
+    type SerializeTupleStruct = SerializeList;
+    type SerializeTupleVariant = SerializeList;
+    type SerializeMap = SerializeBindings;
+    type SerializeStruct = SerializeBindings;
+    type SerializeStructVariant = SerializeBindings;
+
+    fn serialize_bool(self, v: bool) -> Result<Expression> {
+        Ok(EasyConfig::serialize(&v))
+    }
+    fn serialize_i8(self, v: i8) -> Result<Expression> {
+        Ok(EasyConfig::serialize(&v))
+    }
+    fn serialize_i16(self, v: i16) -> Result<Expression> {
+        Ok(EasyConfig::serialize(&v))
+    }
+    fn serialize_i32(self, v: i32) -> Result<Expression> {
+        Ok(EasyConfig::serialize(&v))
+    }
+    fn serialize_i64(self, v: i64) -> Result<Expression> {
+        Ok(EasyConfig::serialize(&v))
+    }
+    fn serialize_i128(self, v: i128) -> Result<Expression> {
+        Ok(EasyConfig::serialize(&v))
+    }
+    fn serialize_u8(self, v: u8) -> Result<Expression> {
+        Ok(EasyConfig::serialize(&v))
+    }
+    fn serialize_u16(self, v: u16) -> Result<Expression> {
+        Ok(EasyConfig::serialize(&v))
+    }
+    fn serialize_u32(self, v: u32) -> Result<Expression> {
+        Ok(EasyConfig::serialize(&v))
+    }
+    fn serialize_u64(self, v: u64) -> Result<Expression> {
+        Ok(EasyConfig::serialize(&v))
+    }
+    fn serialize_u128(self, v: u128) -> Result<Expression> {
+        Ok(EasyConfig::serialize(&v))
+    }
+    fn serialize_f32(self, v: f32) -> Result<Expression> {
+        Ok(EasyConfig::serialize(&v))
+    }
+    fn serialize_f64(self, v: f64) -> Result<Expression> {
+        Ok(EasyConfig::serialize(&v))
+    }
+    fn serialize_char(self, v: char) -> Result<Expression> {
+        Ok(EasyConfig::serialize(&v))
+    }
+    fn serialize_str(self, v: &str) -> Result<Expression> {
+        Ok(EasyConfig::serialize(&v.to_string()))
+    }
+    fn serialize_bytes(self, v: &[u8]) -> Result<Expression> {
+        Ok(EasyConfig::serialize(&v.to_vec()))
+    }
+
+    fn serialize_none(self) -> Result<Expression> {
+        Ok(Expression::presence("None"))
+    }
+    fn serialize_some<T: ?Sized + Serialize>(self, value: &T) -> Result<Expression> {
+        Ok(Expression::list(vec![
+            Expression::presence("Some"),
+            Expression::list(vec![value.serialize(self)?.minimized()]),
+        ]))
+    }
+
+    fn serialize_unit(self) -> Result<Expression> {
+        Ok(Expression::list(vec![]))
+    }
+    fn serialize_unit_struct(self, name: &'static str) -> Result<Expression> {
+        Ok(named(name, vec![]))
+    }
+    fn serialize_unit_variant(self, _name: &'static str, _variant_index: u32, variant_name: &'static str) -> Result<Expression> {
+        Ok(variant(variant_name, vec![]))
+    }
+
+    fn serialize_newtype_struct<T: ?Sized + Serialize>(self, name: &'static str, value: &T) -> Result<Expression> {
+        Ok(named(name, vec![value.serialize(self)?]))
+    }
+    fn serialize_newtype_variant<T: ?Sized + Serialize>(self, _name: &'static str, _variant_index: u32, variant_name: &'static str, value: &T) -> Result<Expression> {
+        Ok(variant(variant_name, vec![value.serialize(self)?]))
+    }
+
+    fn serialize_seq(self, len: Option<usize>) -> Result<SerializeList> {
+        Ok(SerializeList::new(ListKind::Seq, len.unwrap_or(0)))
+    }
+    fn serialize_tuple(self, len: usize) -> Result<SerializeList> {
+        Ok(SerializeList::new(ListKind::Tuple, len))
+    }
+    fn serialize_tuple_struct(self, name: &'static str, len: usize) -> Result<SerializeList> {
+        Ok(SerializeList::new(ListKind::Struct(name), len))
+    }
+    fn serialize_tuple_variant(self, _name: &'static str, _variant_index: u32, variant_name: &'static str, len: usize) -> Result<SerializeList> {
+        Ok(SerializeList::new(ListKind::Variant(variant_name), len))
+    }
+
+    fn serialize_map(self, len: Option<usize>) -> Result<SerializeBindings> {
+        Ok(SerializeBindings::new(ListKind::Seq, len.unwrap_or(0)))
+    }
+    fn serialize_struct(self, name: &'static str, len: usize) -> Result<SerializeBindings> {
+        Ok(SerializeBindings::new(ListKind::Struct(name), len))
+    }
+    fn serialize_struct_variant(self, _name: &'static str, _variant_index: u32, variant_name: &'static str, len: usize) -> Result<SerializeBindings> {
+        Ok(SerializeBindings::new(ListKind::Variant(variant_name), len))
+    }
+}
+
+// What surrounds the elements of a list once it is finished.
+#[derive(Debug, Copy, Clone)]
+enum ListKind {
+    /// Sequences and maps, which are minimized the same way `Vec` and `HashMap` are.
+    Seq,
+    Tuple,
+    Struct(&'static str),
+    Variant(&'static str),
+}
+
+impl ListKind {
+    fn finish(self, elements: Vec<Expression>) -> Expression {
+        match self {
+            ListKind::Seq => Expression::list(elements).minimized(),
+            ListKind::Tuple => Expression::list(elements),
+            ListKind::Struct(name) => named(name, elements),
+            ListKind::Variant(name) => variant(name, elements),
+        }
+    }
+}
+
+pub struct SerializeList {
+    kind: ListKind,
+    elements: Vec<Expression>,
+}
+
+impl SerializeList {
+    fn new(kind: ListKind, len: usize) -> Self {
+        Self { kind, elements: Vec::with_capacity(len) }
+    }
+
+    fn push<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<()> {
+        self.elements.push(value.serialize(Serializer)?);
+        Ok(())
+    }
+}
+
+impl ser::SerializeSeq for SerializeList {
+    type Ok = Expression;
+    type Error = SerializationError;
+
+    fn serialize_element<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<()> {
+        self.push(value)
+    }
+    fn end(self) -> Result<Expression> {
+        Ok(self.kind.finish(self.elements))
+    }
+}
+impl ser::SerializeTuple for SerializeList {
+    type Ok = Expression;
+    type Error = SerializationError;
+
+    fn serialize_element<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<()> {
+        self.push(value)
+    }
+    fn end(self) -> Result<Expression> {
+        Ok(self.kind.finish(self.elements))
+    }
+}
+impl ser::SerializeTupleStruct for SerializeList {
+    type Ok = Expression;
+    type Error = SerializationError;
+
+    fn serialize_field<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<()> {
+        self.push(value)
+    }
+    fn end(self) -> Result<Expression> {
+        Ok(self.kind.finish(self.elements))
+    }
+}
+impl ser::SerializeTupleVariant for SerializeList {
+    type Ok = Expression;
+    type Error = SerializationError;
+
+    fn serialize_field<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<()> {
+        self.push(value)
+    }
+    fn end(self) -> Result<Expression> {
+        Ok(self.kind.finish(self.elements))
+    }
+}
+
+pub struct SerializeBindings {
+    kind: ListKind,
+    bindings: Vec<Expression>,
+    key: Option<String>,
+}
+
+impl SerializeBindings {
+    fn new(kind: ListKind, len: usize) -> Self {
+        Self { kind, bindings: Vec::with_capacity(len), key: None }
+    }
+
+    fn bind<T: ?Sized + Serialize>(&mut self, key: impl AsRef<str>, value: &T) -> Result<()> {
+        self.bindings.push(Expression::binding(key, value.serialize(Serializer)?));
+        Ok(())
+    }
+}
+
+impl ser::SerializeMap for SerializeBindings {
+    type Ok = Expression;
+    type Error = SerializationError;
+
+    fn serialize_key<T: ?Sized + Serialize>(&mut self, key: &T) -> Result<()> {
+        self.key = Some(key.serialize(KeySerializer)?);
+        Ok(())
+    }
+    fn serialize_value<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<()> {
+        let key = self.key.take().expect("serialize_value is always called after serialize_key");
+        self.bind(key, value)
+    }
+    fn end(self) -> Result<Expression> {
+        Ok(self.kind.finish(self.bindings))
+    }
+}
+impl ser::SerializeStruct for SerializeBindings {
+    type Ok = Expression;
+    type Error = SerializationError;
+
+    fn serialize_field<T: ?Sized + Serialize>(&mut self, key: &'static str, value: &T) -> Result<()> {
+        self.bind(key, value)
+    }
+    fn end(self) -> Result<Expression> {
+        Ok(self.kind.finish(self.bindings))
+    }
+}
+impl ser::SerializeStructVariant for SerializeBindings {
+    type Ok = Expression;
+    type Error = SerializationError;
+
+    fn serialize_field<T: ?Sized + Serialize>(&mut self, key: &'static str, value: &T) -> Result<()> {
+        self.bind(key, value)
+    }
+    fn end(self) -> Result<Expression> {
+        Ok(self.kind.finish(self.bindings))
+    }
+}
+
+// Binding names are strings, so map keys have to be something which can be written as one.
+struct KeySerializer;
+
+fn key_error() -> SerializationError {
+    ser::Error::custom("map keys must be strings, characters, integers, or bools")
+}
+
+impl ser::Serializer for KeySerializer {
+    type Ok = String;
+    type Error = SerializationError;
+
+    type SerializeSeq = ser::Impossible<String, SerializationError>;
+    type SerializeTuple = ser::Impossible<String, SerializationError>;
+    type SerializeTupleStruct = ser::Impossible<String, SerializationError>;
+    type SerializeTupleVariant = ser::Impossible<String, SerializationError>;
+    type SerializeMap = ser::Impossible<String, SerializationError>;
+    type SerializeStruct = ser::Impossible<String, SerializationError>;
+    type SerializeStructVariant = ser::Impossible<String, SerializationError>;
+
+    fn serialize_bool(self, v: bool) -> Result<String> {
+        Ok(v.to_string())
+    }
+    fn serialize_i8(self, v: i8) -> Result<String> {
+        Ok(v.to_string())
+    }
+    fn serialize_i16(self, v: i16) -> Result<String> {
+        Ok(v.to_string())
+    }
+    fn serialize_i32(self, v: i32) -> Result<String> {
+        Ok(v.to_string())
+    }
+    fn serialize_i64(self, v: i64) -> Result<String> {
+        Ok(v.to_string())
+    }
+    fn serialize_i128(self, v: i128) -> Result<String> {
+        Ok(v.to_string())
+    }
+    fn serialize_u8(self, v: u8) -> Result<String> {
+        Ok(v.to_string())
+    }
+    fn serialize_u16(self, v: u16) -> Result<String> {
+        Ok(v.to_string())
+    }
+    fn serialize_u32(self, v: u32) -> Result<String> {
+        Ok(v.to_string())
+    }
+    fn serialize_u64(self, v: u64) -> Result<String> {
+        Ok(v.to_string())
+    }
+    fn serialize_u128(self, v: u128) -> Result<String> {
+        Ok(v.to_string())
+    }
+    fn serialize_f32(self, _v: f32) -> Result<String> {
+        Err(key_error())
+    }
+    fn serialize_f64(self, _v: f64) -> Result<String> {
+        Err(key_error())
+    }
+    fn serialize_char(self, v: char) -> Result<String> {
+        Ok(v.to_string())
+    }
+    fn serialize_str(self, v: &str) -> Result<String> {
+        Ok(v.to_string())
+    }
+    fn serialize_bytes(self, _v: &[u8]) -> Result<String> {
+        Err(key_error())
+    }
+    fn serialize_none(self) -> Result<String> {
+        Err(key_error())
+    }
+    fn serialize_some<T: ?Sized + Serialize>(self, _value: &T) -> Result<String> {
+        Err(key_error())
+    }
+    fn serialize_unit(self) -> Result<String> {
+        Err(key_error())
+    }
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<String> {
+        Err(key_error())
+    }
+    fn serialize_unit_variant(self, _name: &'static str, _variant_index: u32, variant: &'static str) -> Result<String> {
+        Ok(variant.to_string())
+    }
+    fn serialize_newtype_struct<T: ?Sized + Serialize>(self, _name: &'static str, value: &T) -> Result<String> {
+        value.serialize(self)
+    }
+    fn serialize_newtype_variant<T: ?Sized + Serialize>(self, _name: &'static str, _variant_index: u32, _variant: &'static str, _value: &T) -> Result<String> {
+        Err(key_error())
+    }
+    fn serialize_seq(self, _len: Option<usize>) -> Result<Self::SerializeSeq> {
+        Err(key_error())
+    }
+    fn serialize_tuple(self, _len: usize) -> Result<Self::SerializeTuple> {
+        Err(key_error())
+    }
+    fn serialize_tuple_struct(self, _name: &'static str, _len: usize) -> Result<Self::SerializeTupleStruct> {
+        Err(key_error())
+    }
+    fn serialize_tuple_variant(self, _name: &'static str, _variant_index: u32, _variant: &'static str, _len: usize) -> Result<Self::SerializeTupleVariant> {
+        Err(key_error())
+    }
+    fn serialize_map(self, _len: Option<usize>) -> Result<Self::SerializeMap> {
+        Err(key_error())
+    }
+    fn serialize_struct(self, _name: &'static str, _len: usize) -> Result<Self::SerializeStruct> {
+        Err(key_error())
+    }
+    fn serialize_struct_variant(self, _name: &'static str, _variant_index: u32, _variant: &'static str, _len: usize) -> Result<Self::SerializeStructVariant> {
+        Err(key_error())
+    }
+}
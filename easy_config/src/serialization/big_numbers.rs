@@ -0,0 +1,154 @@
+//! Numbers which don't fit in a primitive: `Decimal` behind the `rust_decimal` feature, and
+//! `BigInt` and `BigUint` behind the `num-bigint` feature. They are read from the literal text in
+//! the config, never through an `f64`, so `0.1` is exactly one tenth and integers past `u128` keep
+//! every digit.
+
+#[cfg(feature = "num-bigint")]
+use num_bigint::{BigInt, BigUint, Sign};
+#[cfg(feature = "rust_decimal")]
+use rust_decimal::Decimal;
+#[cfg(feature = "num-bigint")]
+use crate::atom::Integer;
+use crate::config_error::Contextualize;
+use crate::expression::{Atom, Expression};
+use crate::expression_iterator::ExpressionIterator;
+use crate::serialization::EasyConfig;
+use crate::serialization::primitives::next_atom;
+use crate::serialization::serialization_error::{Kind, SerializationError};
+
+#[cfg(feature = "rust_decimal")]
+impl EasyConfig for Decimal {
+    fn serialize(&self) -> Expression {
+        Expression::presence(Atom::classify(self.to_string()))
+    }
+
+    fn deserialize(exprs: &mut ExpressionIterator, source_text: impl AsRef<str>) -> Result<Self, SerializationError> {
+        let source_text = source_text.as_ref();
+        let (atom, span) = next_atom(exprs, source_text)?;
+        let literal = match atom {
+            // Written out in base 10, since that's all `Decimal` reads.
+            Atom::Integer(i) => format!("{}{}", if i.is_negative() { "-" } else { "" }, i.magnitude()),
+            Atom::Float(f) => f.literal().to_string(),
+            Atom::Number(n) => n,
+            other => return Err(SerializationError::on_span(Kind::ExpectedNumber(other.to_string()), span, source_text))
+                .contextualize("Error while deserializing a Decimal"),
+        };
+
+        let parsed = if literal.contains(['e', 'E']) {
+            Decimal::from_scientific(&literal)
+        } else {
+            Decimal::from_str_exact(&literal)
+        };
+        parsed
+            .map_err(|e| SerializationError::on_span(
+                Kind::Malformed { value: literal.clone(), target: "Decimal", reason: e.to_string() },
+                span,
+                source_text
+            ))
+            .contextualize("Error while deserializing a Decimal")
+    }
+}
+
+#[cfg(feature = "num-bigint")]
+fn next_big_integer(exprs: &mut ExpressionIterator, source_text: &str, target: &'static str, signed: bool) -> Result<(bool, BigUint), SerializationError> {
+    let (atom, span) = next_atom(exprs, source_text)?;
+    match atom {
+        Atom::Integer(i) => Ok((i.is_negative(), BigUint::from(i.magnitude()))),
+        // Integers too big for a u128 are left as raw numbers by the parser.
+        Atom::Number(n) => {
+            let wide = Integer::literal_digits(&n).and_then(|(negative, radix, digits)| {
+                BigUint::from_radix_be(&digits, radix.base()).map(|magnitude| (negative, magnitude))
+            });
+            wide.ok_or_else(|| SerializationError::on_span(Kind::ExpectedInteger(n), span, source_text))
+        }
+        other => Err(SerializationError::on_span(Kind::ExpectedInteger(other.to_string()), span, source_text)),
+    }.and_then(|(negative, magnitude)| {
+        if !signed && negative && magnitude != BigUint::ZERO {
+            return Err(SerializationError::on_span(
+                Kind::IntegerOutOfRange {
+                    literal: format!("-{}", magnitude),
+                    target,
+                    range: "only non-negative integers".to_string(),
+                },
+                span,
+                source_text
+            ))
+        }
+        Ok((negative, magnitude))
+    }).contextualize(format!("Error while deserializing a {}", target))
+}
+
+#[cfg(feature = "num-bigint")]
+impl EasyConfig for BigInt {
+    fn serialize(&self) -> Expression {
+        Expression::presence(Atom::classify(self.to_string()))
+    }
+
+    fn deserialize(exprs: &mut ExpressionIterator, source_text: impl AsRef<str>) -> Result<Self, SerializationError> {
+        let (negative, magnitude) = next_big_integer(exprs, source_text.as_ref(), "BigInt", true)?;
+        Ok(BigInt::from_biguint(if negative { Sign::Minus } else { Sign::Plus }, magnitude))
+    }
+}
+
+#[cfg(feature = "num-bigint")]
+impl EasyConfig for BigUint {
+    fn serialize(&self) -> Expression {
+        Expression::presence(Atom::classify(self.to_string()))
+    }
+
+    fn deserialize(exprs: &mut ExpressionIterator, source_text: impl AsRef<str>) -> Result<Self, SerializationError> {
+        let (_, magnitude) = next_big_integer(exprs, source_text.as_ref(), "BigUint", false)?;
+        Ok(magnitude)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::serialization::tests::parse;
+    use super::*;
+
+    #[cfg(feature = "rust_decimal")]
+    #[test]
+    fn decimals_are_exact() {
+        assert_eq!(parse::<Decimal>("0.1").unwrap(), Decimal::new(1, 1));
+        assert_eq!(parse::<Decimal>("-12.345").unwrap(), Decimal::new(-12345, 3));
+        assert_eq!(parse::<Decimal>("1.5e3").unwrap(), Decimal::new(1500, 0));
+        assert_eq!(parse::<Decimal>("0x10").unwrap(), Decimal::new(16, 0));
+        // `Decimal` has an inherent `serialize` of its own, so the trait is named explicitly.
+        assert_eq!(EasyConfig::serialize(&parse::<Decimal>("0.10").unwrap()).dump(), "0.10");
+
+        let precise = "0.1234567890123456789012345678";
+        assert_eq!(EasyConfig::serialize(&parse::<Decimal>(precise).unwrap()).dump(), precise);
+    }
+
+    #[cfg(feature = "rust_decimal")]
+    #[test]
+    fn invalid_decimals() {
+        let error = parse::<Decimal>("79228162514264337593543950336").unwrap_err();
+        assert!(matches!(error.kind(), Kind::Malformed { target: "Decimal", .. }));
+        assert!(matches!(parse::<Decimal>("cat").unwrap_err().kind(), Kind::ExpectedNumber(_)));
+    }
+
+    #[cfg(feature = "num-bigint")]
+    #[test]
+    fn integers_past_u128() {
+        let source = "-3402823669209384634633746074317682114550";
+        let big = parse::<BigInt>(source).unwrap();
+        assert_eq!(big, BigInt::from(u128::MAX) * -10);
+        assert_eq!(big.serialize().dump(), source);
+
+        assert_eq!(parse::<BigInt>("42").unwrap(), BigInt::from(42));
+        assert_eq!(parse::<BigUint>("0x1_0000_0000_0000_0000_0000_0000_0000_0000").unwrap(), BigUint::from(u128::MAX) + 1u32);
+    }
+
+    #[cfg(feature = "num-bigint")]
+    #[test]
+    fn invalid_big_integers() {
+        assert!(matches!(parse::<BigInt>("1.5").unwrap_err().kind(), Kind::ExpectedInteger(_)));
+        assert!(matches!(parse::<BigInt>("99999999999999999999999999999999999999999x").unwrap_err().kind(), Kind::ExpectedInteger(_)));
+
+        let error = parse::<BigUint>("-1").unwrap_err();
+        assert!(matches!(error.kind(), Kind::IntegerOutOfRange { target: "BigUint", .. }));
+        assert_eq!(parse::<BigUint>("-0").unwrap(), BigUint::ZERO);
+    }
+}
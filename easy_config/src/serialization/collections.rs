@@ -1,33 +1,61 @@
 use std::any;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, BTreeSet, BinaryHeap, HashMap, HashSet, LinkedList, VecDeque};
+use std::hash::Hash;
+#[cfg(feature = "indexmap")]
+use indexmap::IndexMap;
+use itertools::Itertools;
 use crate::config_error::Contextualize;
-use crate::expression::{Expression, ExpressionData};
+use crate::expression::{Atom, Expression, ExpressionData};
 use crate::expression_iterator::ExpressionIterator;
 use crate::lexical_span::LexicalSpan;
 use crate::serialization::EasyConfig;
+use crate::serialization::map_key::MapKey;
 use crate::serialization::serialization_error::{Kind, SerializationError};
 
 fn serialize_linear<'a, T: EasyConfig>(elements: impl Iterator<Item=&'a T>) -> Expression {
     Expression::list(elements.map(|x| x.serialize()).collect()).minimized()
 }
 
-fn deserialize_linear<'a, T: EasyConfig>(elements: &mut ExpressionIterator, source_text: impl AsRef<str>) -> Result<Vec<T>, SerializationError> {
-    let source_text = source_text.as_ref();
-
+// Each element along with the span it was read from.
+fn deserialize_spanned<T: EasyConfig>(elements: &mut ExpressionIterator, source_text: &str) -> Result<Vec<(T, LexicalSpan)>, SerializationError> {
     if T::PASSTHROUGH {
         let mut collection = Vec::new();
-        while elements.len() != 0 {
-            collection.push(T::deserialize(elements, source_text)?)
+        while let Some(span) = elements.peek().map(|x| x.span()) {
+            collection.push((T::deserialize(elements, source_text)?, span))
         };
 
         return Ok(collection)
     }
 
     Result::from_iter(elements.map(|x| {
-        T::deserialize(&mut x.into_iter(), source_text)
+        let span = x.span();
+        Ok((T::deserialize(&mut x.into_iter(), source_text)?, span))
     }))
 }
 
+fn deserialize_linear<T: EasyConfig>(elements: &mut ExpressionIterator, source_text: impl AsRef<str>) -> Result<Vec<T>, SerializationError> {
+    Ok(deserialize_spanned(elements, source_text.as_ref())?.into_iter().map(|(x, _)| x).collect())
+}
+
+// Build a set, rejecting an element which is already present on its own span.
+fn deserialize_set<T: EasyConfig, S: Default>(
+    exprs: &mut ExpressionIterator,
+    source_text: &str,
+    insert: impl Fn(&mut S, T) -> Result<(), T>,
+) -> Result<S, SerializationError> {
+    let mut set = S::default();
+    for (element, span) in deserialize_spanned(exprs, source_text)? {
+        if let Err(duplicate) = insert(&mut set, element) {
+            return Err(SerializationError::on_span(
+                Kind::DuplicateElement(duplicate.serialize().uncomented_dump()),
+                span,
+                source_text
+            ))
+        }
+    }
+    Ok(set)
+}
+
 impl<T: EasyConfig> EasyConfig for Vec<T> {
     fn serialize(&self) -> Expression {
         serialize_linear(self.iter())
@@ -39,6 +67,92 @@ impl<T: EasyConfig> EasyConfig for Vec<T> {
             .contextualize(format!("Error while deserializing Vec<{}>", any::type_name::<T>()))
     }
 }
+
+macro_rules! config_linear {
+    ($collection: ident) => {
+        impl<T: EasyConfig> EasyConfig for $collection<T> {
+            fn serialize(&self) -> Expression {
+                serialize_linear(self.iter())
+            }
+
+            fn deserialize(exprs: &mut ExpressionIterator, source_text: impl AsRef<str>) -> Result<Self, SerializationError> {
+                Ok(deserialize_linear(exprs, source_text)
+                    .contextualize(format!("Error while deserializing {}<{}>", stringify!($collection), any::type_name::<T>()))?
+                    .into_iter()
+                    .collect())
+            }
+        }
+    };
+}
+
+config_linear!(VecDeque);
+config_linear!(LinkedList);
+
+impl<T: EasyConfig> EasyConfig for Box<[T]> {
+    fn serialize(&self) -> Expression {
+        serialize_linear(self.iter())
+    }
+
+    fn deserialize(exprs: &mut ExpressionIterator, source_text: impl AsRef<str>) -> Result<Self, SerializationError> {
+        Ok(deserialize_linear(exprs, source_text)
+            .contextualize(format!("Error while deserializing Box<[{}]>", any::type_name::<T>()))?
+            .into_boxed_slice())
+    }
+}
+
+// A heap has no meaningful order of its own, so it is written in ascending order.
+impl<T: EasyConfig + Ord> EasyConfig for BinaryHeap<T> {
+    fn serialize(&self) -> Expression {
+        serialize_linear(self.iter().sorted())
+    }
+
+    fn deserialize(exprs: &mut ExpressionIterator, source_text: impl AsRef<str>) -> Result<Self, SerializationError> {
+        Ok(BinaryHeap::from(deserialize_linear(exprs, source_text)
+            .contextualize(format!("Error while deserializing BinaryHeap<{}>", any::type_name::<T>()))?))
+    }
+}
+
+impl<T: EasyConfig + Ord> EasyConfig for BTreeSet<T> {
+    fn serialize(&self) -> Expression {
+        serialize_linear(self.iter())
+    }
+
+    fn deserialize(exprs: &mut ExpressionIterator, source_text: impl AsRef<str>) -> Result<Self, SerializationError> {
+        let source_text = source_text.as_ref();
+        deserialize_set(exprs, source_text, |set: &mut BTreeSet<T>, x| {
+            if set.contains(&x) {
+                return Err(x)
+            }
+            set.insert(x);
+            Ok(())
+        }).contextualize(format!("Error while deserializing BTreeSet<{}>", any::type_name::<T>()))
+    }
+}
+
+// Hash order changes between runs, so elements are written in the canonical order of their
+// serialized form instead.
+impl<T: EasyConfig + Eq + Hash> EasyConfig for HashSet<T> {
+    fn serialize(&self) -> Expression {
+        Expression::list(
+            self.iter()
+                .map(|x| x.serialize())
+                .sorted_by(|a, b| a.semantic_cmp(b))
+                .collect()
+        ).minimized()
+    }
+
+    fn deserialize(exprs: &mut ExpressionIterator, source_text: impl AsRef<str>) -> Result<Self, SerializationError> {
+        let source_text = source_text.as_ref();
+        deserialize_set(exprs, source_text, |set: &mut HashSet<T>, x| {
+            if set.contains(&x) {
+                return Err(x)
+            }
+            set.insert(x);
+            Ok(())
+        }).contextualize(format!("Error while deserializing HashSet<{}>", any::type_name::<T>()))
+    }
+}
+
 impl<T: EasyConfig, const N: usize> EasyConfig for [T; N] {
     fn serialize(&self) -> Expression {
         serialize_linear(self.iter())
@@ -65,7 +179,7 @@ impl<T: EasyConfig, const N: usize> EasyConfig for [T; N] {
     }
 }
 
-fn deserialize_hashmap_binding<T: EasyConfig>(expression: Expression, source_text: impl AsRef<str>) -> Result<(String, T), SerializationError> {
+fn deserialize_map_binding<K: MapKey, T: EasyConfig>(expression: Expression, source_text: &str) -> Result<(K, T), SerializationError> {
     let span = expression.span();
     let ExpressionData::BindingExpr(binding) = expression.data else {
         return Err(SerializationError::on_span(
@@ -75,30 +189,74 @@ fn deserialize_hashmap_binding<T: EasyConfig>(expression: Expression, source_tex
         ))
     };
 
-    Ok((binding.name, T::deserialize(&mut binding.value.into_iter(), source_text)?))
+    let Some(key) = K::from_key(&binding.name) else {
+        return Err(SerializationError::on_span(
+            Kind::InvalidMapKey { key: binding.name, target: any::type_name::<K>() },
+            span,
+            source_text
+        ))
+    };
+    Ok((key, T::deserialize(&mut binding.value.into_iter(), source_text)?))
+}
+
+fn deserialize_map<K: MapKey, T: EasyConfig>(exprs: &mut ExpressionIterator, source_text: &str) -> Result<Vec<(K, T)>, SerializationError> {
+    Result::from_iter(exprs.map(|x| deserialize_map_binding(x, source_text)))
 }
-impl<T: EasyConfig> EasyConfig for HashMap<String, T> {
+
+fn serialize_map<'a, K: MapKey, T: EasyConfig + 'a>(entries: impl Iterator<Item=(&'a K, &'a T)>) -> Expression {
+    Expression::list(
+        entries
+            .map(|(k, v)| Expression::binding(
+                k.to_key(),
+                v.serialize(),
+            ))
+            .collect(),
+    ).minimized()
+}
+
+// Written in the order of the keys' atoms, so integer keys are in numeric order and everything
+// else is in lexical order, rather than in hash order which changes between runs.
+impl<K: MapKey + Eq + Hash, T: EasyConfig> EasyConfig for HashMap<K, T> {
     fn serialize(&self) -> Expression {
-        Expression::list(
-            self.iter()
-                .map(|(k, v)| Expression::binding(
-                    k.to_string(),
-                    v.serialize(),
-                ))
-                .collect(),
-        ).minimized()
+        serialize_map(self.iter().sorted_by_cached_key(|(k, _)| {
+            let key = k.to_key();
+            (Atom::classify(&key), key)
+        }))
     }
     fn deserialize(exprs: &mut ExpressionIterator, source_text: impl AsRef<str>) -> Result<Self, SerializationError> {
-        let source_text = source_text.as_ref();
-        let list: Vec<(String, T)> = Result::from_iter(exprs.map(|x| deserialize_hashmap_binding(x, source_text)))
-            .contextualize(format!("Error while deserializing Hashmap<String, {}>", any::type_name::<T>()))?;
+        let list = deserialize_map(exprs, source_text.as_ref())
+            .contextualize(format!("Error while deserializing Hashmap<{}, {}>", any::type_name::<K>(), any::type_name::<T>()))?;
         Ok(HashMap::from_iter(list))
     }
 }
 
+impl<K: MapKey + Ord, T: EasyConfig> EasyConfig for BTreeMap<K, T> {
+    fn serialize(&self) -> Expression {
+        serialize_map(self.iter())
+    }
+    fn deserialize(exprs: &mut ExpressionIterator, source_text: impl AsRef<str>) -> Result<Self, SerializationError> {
+        let list = deserialize_map(exprs, source_text.as_ref())
+            .contextualize(format!("Error while deserializing BTreeMap<{}, {}>", any::type_name::<K>(), any::type_name::<T>()))?;
+        Ok(BTreeMap::from_iter(list))
+    }
+}
+
+// Entries are kept in the order they were written in.
+#[cfg(feature = "indexmap")]
+impl<K: MapKey + Eq + Hash, T: EasyConfig> EasyConfig for IndexMap<K, T> {
+    fn serialize(&self) -> Expression {
+        serialize_map(self.iter())
+    }
+    fn deserialize(exprs: &mut ExpressionIterator, source_text: impl AsRef<str>) -> Result<Self, SerializationError> {
+        let list = deserialize_map(exprs, source_text.as_ref())
+            .contextualize(format!("Error while deserializing IndexMap<{}, {}>", any::type_name::<K>(), any::type_name::<T>()))?;
+        Ok(IndexMap::from_iter(list))
+    }
+}
 
 #[cfg(test)]
 mod tests {
+    use crate::serialization::tests::parse;
     use super::*;
 
     #[test]
@@ -140,4 +298,75 @@ mod tests {
             assert_eq!(Some(&v), expected.get(&k));
         }
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn btreemap() {
+        let expected = BTreeMap::from([(String::from("b"), 2), (String::from("a"), 1)]);
+        assert_eq!(expected.serialize().dump(), "(a = 1 b = 2)");
+        assert_eq!(parse::<BTreeMap<String, i32>>("b = 2 a = 1").unwrap(), expected);
+    }
+
+    #[test]
+    fn hashmap_is_written_in_key_order() {
+        let words = HashMap::from([("pear", 1), ("apple", 2), ("fig", 3)].map(|(k, v)| (k.to_string(), v)));
+        assert_eq!(words.serialize().dump(), "(apple = 2 fig = 3 pear = 1)");
+
+        let ports = HashMap::from([(10_u16, 'b'), (9, 'a'), (100, 'c')]);
+        assert_eq!(ports.serialize().dump(), "(9 = a 10 = b 100 = c)");
+    }
+
+    #[cfg(feature = "indexmap")]
+    #[test]
+    fn indexmap_keeps_file_order() {
+        let map = parse::<IndexMap<String, i32>>("b = 2 c = 3 a = 1").unwrap();
+        assert_eq!(map.keys().collect_vec(), vec!["b", "c", "a"]);
+        assert_eq!(map.serialize().dump(), "(b = 2 c = 3 a = 1)");
+    }
+
+    #[test]
+    fn integer_keys() {
+        let expected = BTreeMap::from([(8081_u16, String::from("b")), (8080, String::from("a"))]);
+        assert_eq!(expected.serialize().dump(), "(8080 = a 8081 = b)");
+        assert_eq!(parse::<BTreeMap<u16, String>>("8081 = b 8080 = a").unwrap(), expected);
+        assert_eq!(parse::<HashMap<char, bool>>("\"=\" = true").unwrap(), HashMap::from([('=', true)]));
+    }
+
+    #[test]
+    fn invalid_key() {
+        let error = parse::<HashMap<u8, i32>>("1 = 1\n300 = 2").unwrap_err();
+        assert!(matches!(error.kind(), Kind::InvalidMapKey { key, target: "u8" } if key == "300"));
+        assert!(error.to_string().contains("Error at 2:1"), "{}", error);
+    }
+
+    #[test]
+    fn linear_collections() {
+        assert_eq!(parse::<VecDeque<u8>>("3 1 2").unwrap(), VecDeque::from([3, 1, 2]));
+        assert_eq!(parse::<LinkedList<u8>>("3 1 2").unwrap(), LinkedList::from([3, 1, 2]));
+        assert_eq!(parse::<Box<[u8]>>("3 1 2").unwrap(), Box::from([3, 1, 2]));
+        assert_eq!(VecDeque::from([1, 2]).serialize().dump(), "(1 2)");
+    }
+
+    #[test]
+    fn binary_heap() {
+        let heap = parse::<BinaryHeap<u8>>("3 1 2").unwrap();
+        assert_eq!(heap.peek(), Some(&3));
+        assert_eq!(heap.serialize().dump(), "(1 2 3)");
+    }
+
+    #[test]
+    fn sets() {
+        assert_eq!(parse::<BTreeSet<u8>>("3 1 2").unwrap(), BTreeSet::from([1, 2, 3]));
+        assert_eq!(parse::<HashSet<u8>>("3 1 2").unwrap(), HashSet::from([1, 2, 3]));
+        assert_eq!(HashSet::from([3, 10, 1, 2]).serialize().dump(), "(1 2 3 10)");
+    }
+
+    #[test]
+    fn set_duplicates_point_at_the_second_occurrence() {
+        let error = parse::<BTreeSet<u8>>("1 2 1").unwrap_err();
+        assert!(matches!(error.kind(), Kind::DuplicateElement(e) if e == "1"));
+        assert!(error.to_string().contains("Error at 1:5"), "{}", error);
+
+        let error = parse::<HashSet<String>>("(a b) (c) (a b)").unwrap_err();
+        assert!(error.to_string().contains("Error at 1:11"), "{}", error);
+    }
+}
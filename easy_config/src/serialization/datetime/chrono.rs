@@ -0,0 +1,129 @@
+use ::chrono::{DateTime, Datelike, FixedOffset, NaiveDate, NaiveDateTime, NaiveTime, Offset, SecondsFormat, TimeZone, Timelike, Utc};
+
+const NAIVE_DATE_TIME: &str = "%Y-%m-%dT%H:%M:%S%.f";
+const OFFSET_DATE_TIME: &str = "%Y-%m-%dT%H:%M:%S%.f%::z";
+
+// RFC 3339 has no room for years outside 0000 to 9999 or offsets with seconds, so dates and times
+// which have either are written with a signed year and the offset's seconds instead.
+fn write_date_time<Tz: TimeZone>(date_time: &DateTime<Tz>) -> String where Tz::Offset: std::fmt::Display {
+    let offset = date_time.offset().fix().local_minus_utc();
+    if (0..=9999).contains(&date_time.year()) && offset % 60 == 0 {
+        date_time.to_rfc3339_opts(SecondsFormat::AutoSi, true)
+    } else {
+        date_time.fixed_offset().format(OFFSET_DATE_TIME).to_string()
+    }
+}
+
+// chrono can write an offset's seconds but not read them, so the offset is read separately.
+fn parse_date_time(text: &str) -> Option<DateTime<FixedOffset>> {
+    if let Ok(date_time) = DateTime::parse_from_rfc3339(text) {
+        return Some(date_time)
+    }
+
+    let (date_time, offset) = text.split_at_checked(text.len().checked_sub("+00:00:00".len())?)?;
+    let sign = match offset.chars().next()? {
+        '+' => 1,
+        '-' => -1,
+        _ => return None,
+    };
+    let seconds = NaiveTime::parse_from_str(&offset[1..], "%H:%M:%S").ok()?.num_seconds_from_midnight() as i32;
+    let offset = FixedOffset::east_opt(sign * seconds)?;
+    NaiveDateTime::parse_from_str(date_time, NAIVE_DATE_TIME).ok()?.and_local_timezone(offset).single()
+}
+
+config_datetime!(
+    DateTime<Utc>,
+    "an RFC 3339 date and time such as 2024-05-01T10:00:00Z",
+    |text| parse_date_time(text).map(|x| x.with_timezone(&Utc)),
+    |time| write_date_time(time)
+);
+config_datetime!(
+    DateTime<FixedOffset>,
+    "an RFC 3339 date and time such as 2024-05-01T10:00:00+02:00",
+    |text| parse_date_time(text),
+    |time| write_date_time(time)
+);
+config_datetime!(
+    NaiveDateTime,
+    "a date and time such as 2024-05-01T10:00:00",
+    |text| NaiveDateTime::parse_from_str(text, NAIVE_DATE_TIME).ok(),
+    |time| time.format(NAIVE_DATE_TIME).to_string()
+);
+config_datetime!(
+    NaiveDate,
+    "a date such as 2024-05-01",
+    |text| text.parse().ok(),
+    |date| date.to_string()
+);
+config_datetime!(
+    NaiveTime,
+    "a time such as 10:00:00",
+    |text| text.parse().ok(),
+    |time| time.to_string()
+);
+
+#[cfg(test)]
+mod tests {
+    use ::chrono::TimeDelta;
+    use crate::serialization::EasyConfig;
+    use crate::serialization::tests::parse;
+    use crate::serialization::serialization_error::Kind;
+    use super::*;
+
+    #[test]
+    fn date_times() {
+        let utc = parse::<DateTime<Utc>>("2024-05-01T12:00:00+02:00").unwrap();
+        assert_eq!(utc.serialize().dump(), "2024-05-01T10:00:00Z");
+
+        let offset = parse::<DateTime<FixedOffset>>("2024-05-01T12:00:00.5+02:00").unwrap();
+        assert_eq!(offset.serialize().dump(), "2024-05-01T12:00:00.500+02:00");
+        assert_eq!(parse::<DateTime<FixedOffset>>(&offset.serialize().dump()).unwrap(), offset);
+    }
+
+    #[test]
+    fn offsets_with_seconds() {
+        let date_time = NaiveDate::from_ymd_opt(2024, 5, 1).unwrap().and_time(NaiveTime::MIN)
+            .and_local_timezone(FixedOffset::east_opt(3630).unwrap()).unwrap();
+        let dumped = date_time.serialize().dump();
+        assert_eq!(dumped, "2024-05-01T00:00:00+01:00:30");
+        assert_eq!(parse::<DateTime<FixedOffset>>(&dumped).unwrap(), date_time);
+    }
+
+    #[test]
+    fn years_outside_rfc_3339() {
+        let date_time = NaiveDate::from_ymd_opt(-1, 5, 1).unwrap().and_time(NaiveTime::MIN);
+        let offset = date_time.and_local_timezone(FixedOffset::east_opt(7200).unwrap()).unwrap() + TimeDelta::milliseconds(250);
+        let dumped = offset.serialize().dump();
+        assert_eq!(dumped, "-0001-05-01T00:00:00.250+02:00:00");
+        assert_eq!(parse::<DateTime<FixedOffset>>(&dumped).unwrap(), offset);
+
+        let utc = date_time.and_utc();
+        assert_eq!(parse::<DateTime<Utc>>(&utc.serialize().dump()).unwrap(), utc);
+        assert_eq!(parse::<NaiveDateTime>(&date_time.serialize().dump()).unwrap(), date_time);
+        assert_eq!(parse::<NaiveDate>(&date_time.date().serialize().dump()).unwrap(), date_time.date());
+
+        let far = NaiveDate::from_ymd_opt(12024, 5, 1).unwrap().and_time(NaiveTime::MIN).and_utc();
+        assert_eq!(far.serialize().dump(), "+12024-05-01T00:00:00+00:00:00");
+        assert_eq!(parse::<DateTime<Utc>>(&far.serialize().dump()).unwrap(), far);
+    }
+
+    #[test]
+    fn naive() {
+        let date = parse::<NaiveDate>("2024-02-29").unwrap();
+        assert_eq!(date, NaiveDate::from_ymd_opt(2024, 2, 29).unwrap());
+        assert_eq!(date.serialize().dump(), "2024-02-29");
+
+        let time = parse::<NaiveTime>("23:30:05").unwrap();
+        assert_eq!(time.serialize().dump(), "23:30:05");
+
+        let date_time = date.and_time(time);
+        assert_eq!(date_time.serialize().dump(), "2024-02-29T23:30:05");
+        assert_eq!(parse::<NaiveDateTime>("2024-02-29T23:30:05").unwrap(), date_time);
+    }
+
+    #[test]
+    fn invalid() {
+        assert!(matches!(parse::<NaiveDate>("2023-02-29").unwrap_err().kind(), Kind::InvalidValue { .. }));
+        assert!(matches!(parse::<DateTime<Utc>>("2024-05-01T10:00:00").unwrap_err().kind(), Kind::InvalidValue { .. }));
+    }
+}
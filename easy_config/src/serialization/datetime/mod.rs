@@ -0,0 +1,240 @@
+//! Dates and times, written as RFC 3339 literals such as `2024-05-01T10:00:00Z`. Each is a single
+//! token, so it can be written unquoted.
+
+use std::time::{Duration, SystemTime};
+use crate::expression::ExpressionData;
+use crate::expression_iterator::ExpressionIterator;
+use crate::lexical_span::LexicalSpan;
+use crate::serialization::serialization_error::{Kind, SerializationError};
+
+/// Implement `EasyConfig` for a type which can be parsed from, and written as, a single token.
+macro_rules! config_datetime {
+    ($ty: ty, $expected: literal, |$parsed: ident| $parse: expr, |$value: ident| $write: expr) => {
+        impl $crate::serialization::EasyConfig for $ty {
+            fn serialize(&self) -> $crate::expression::Expression {
+                let $value = self;
+                $crate::expression::Expression::presence($crate::serialization::primitives::unquoted_where_possible($write))
+            }
+
+            fn deserialize(exprs: &mut $crate::expression_iterator::ExpressionIterator, source_text: impl AsRef<str>) -> Result<Self, $crate::serialization::serialization_error::SerializationError> {
+                use $crate::config_error::Contextualize;
+                let source_text = source_text.as_ref();
+                let (text, span) = $crate::serialization::datetime::next_text(exprs, source_text)?;
+                let $parsed = text.as_str();
+                let parsed: Option<$ty> = $parse;
+                parsed
+                    .ok_or_else(|| $crate::serialization::serialization_error::SerializationError::on_span(
+                        $crate::serialization::serialization_error::Kind::InvalidValue { value: text, expected: $expected },
+                        span,
+                        source_text
+                    ))
+                    .contextualize(format!("Error while deserializing a {}", stringify!($ty)))
+            }
+        }
+    };
+}
+
+#[cfg(feature = "chrono")]
+mod chrono;
+#[cfg(feature = "time")]
+mod time;
+
+pub(crate) fn next_text(exprs: &mut ExpressionIterator, source_text: &str) -> Result<(String, LexicalSpan), SerializationError> {
+    let expr = exprs.minimized_next_or_err(source_text)?;
+    let span = expr.span();
+    match &expr.data {
+        ExpressionData::Presence(atom, _) => Ok((atom.to_text(), span)),
+        _ => Err(SerializationError::on_span(Kind::ExpectedPresence(expr), span, source_text)),
+    }
+}
+
+const SECONDS_PER_DAY: i64 = 86_400;
+
+// Days since 1970-01-01 of a date in the proleptic Gregorian calendar.
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
+
+// The inverse of `days_from_civil`.
+fn civil_from_days(days: i64) -> (i64, i64, i64) {
+    let days = days + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days - era * 146_097;
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let shifted_month = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * shifted_month + 2) / 5 + 1;
+    let month = if shifted_month < 10 { shifted_month + 3 } else { shifted_month - 9 };
+    let year = year_of_era + era * 400;
+    (if month <= 2 { year + 1 } else { year }, month, day)
+}
+
+fn days_in_month(year: i64, month: i64) -> i64 {
+    days_from_civil(year + month / 12, month % 12 + 1, 1) - days_from_civil(year, month, 1)
+}
+
+fn number(text: &str) -> Option<i64> {
+    text.bytes().all(|x| x.is_ascii_digit()).then(|| text.parse().ok())?
+}
+
+// Years as far from 0 as this can only be written with seconds which don't fit an i64.
+const MAX_YEAR: i64 = 292_277_026_596;
+
+// Seconds and nanoseconds since the epoch of an RFC 3339 timestamp. Like the chrono and time
+// types, years outside 0000 to 9999 may be written with a sign, as in `+12024` or `-0001`.
+fn parse_rfc3339(text: &str) -> Option<(i64, u32)> {
+    let (date, time) = text.split_once(['T', 't'])?;
+    let (sign, unsigned_date) = match date.strip_prefix('-') {
+        Some(rest) => (-1, rest),
+        None => (1, date.strip_prefix('+').unwrap_or(date)),
+    };
+    let signed = unsigned_date.len() != date.len();
+    let [year, month, day] = unsigned_date.splitn(3, '-').collect::<Vec<_>>()[..] else { return None };
+    let year_digits = if signed { 4..=12 } else { 4..=4 };
+    if !year_digits.contains(&year.len()) || month.len() != 2 || day.len() != 2 {
+        return None
+    }
+    let (year, month, day) = (sign * number(year)?, number(month)?, number(day)?);
+    if year.abs() > MAX_YEAR || !(1..=12).contains(&month) || !(1..=days_in_month(year, month)).contains(&day) {
+        return None
+    }
+
+    let offset_at = time.find(['Z', 'z', '+', '-'])?;
+    let (time, offset) = time.split_at(offset_at);
+    let (time, fraction) = time.split_once('.').unwrap_or((time, ""));
+    let [hour, minute, second] = time.splitn(3, ':').collect::<Vec<_>>()[..] else { return None };
+    let (hour, minute, second) = (number(hour)?, number(minute)?, number(second)?);
+    if time.len() != 8 || hour > 23 || minute > 59 || second > 59 {
+        return None
+    }
+
+    let nanos = match fraction {
+        "" if !text.contains('.') => 0,
+        fraction => {
+            number(fraction)?;
+            format!("{:0<9}", &fraction[..fraction.len().min(9)]).parse().ok()?
+        }
+    };
+
+    let offset = match offset {
+        "Z" | "z" => 0,
+        offset => {
+            let (hours, minutes) = offset[1..].split_once(':')?;
+            let (hours, minutes) = (number(hours)?, number(minutes)?);
+            if offset.len() != 6 || hours > 23 || minutes > 59 {
+                return None
+            }
+            let seconds = hours * 3600 + minutes * 60;
+            if offset.starts_with('-') { -seconds } else { seconds }
+        }
+    };
+
+    let seconds = days_from_civil(year, month, day).checked_mul(SECONDS_PER_DAY)?.checked_add(hour * 3600 + minute * 60 + second - offset)?;
+    Some((seconds, nanos))
+}
+
+fn write_rfc3339(seconds: i64, nanos: u32) -> String {
+    let (year, month, day) = civil_from_days(seconds.div_euclid(SECONDS_PER_DAY));
+    let time = seconds.rem_euclid(SECONDS_PER_DAY);
+    let year = if (0..=9999).contains(&year) { format!("{:04}", year) } else { format!("{:+05}", year) };
+    let mut written = format!("{}-{:02}-{:02}T{:02}:{:02}:{:02}", year, month, day, time / 3600, time / 60 % 60, time % 60);
+    if nanos != 0 {
+        written.push('.');
+        written.push_str(format!("{:09}", nanos).trim_end_matches('0'));
+    }
+    written.push('Z');
+    written
+}
+
+fn system_time_from(seconds: i64, nanos: u32) -> Option<SystemTime> {
+    let nanos = Duration::from_nanos(nanos.into());
+    if seconds >= 0 {
+        SystemTime::UNIX_EPOCH.checked_add(Duration::from_secs(seconds as u64))?.checked_add(nanos)
+    } else {
+        SystemTime::UNIX_EPOCH.checked_sub(Duration::from_secs(seconds.unsigned_abs()))?.checked_add(nanos)
+    }
+}
+
+fn system_time_parts(time: &SystemTime) -> (i64, u32) {
+    match time.duration_since(SystemTime::UNIX_EPOCH) {
+        Ok(after) => (after.as_secs() as i64, after.subsec_nanos()),
+        Err(before) => {
+            let before = before.duration();
+            match before.subsec_nanos() {
+                0 => (-(before.as_secs() as i64), 0),
+                nanos => (-(before.as_secs() as i64) - 1, 1_000_000_000 - nanos),
+            }
+        }
+    }
+}
+
+// Written in UTC.
+config_datetime!(
+    SystemTime,
+    "an RFC 3339 date and time such as 2024-05-01T10:00:00Z",
+    |text| parse_rfc3339(text).and_then(|(seconds, nanos)| system_time_from(seconds, nanos)),
+    |time| {
+        let (seconds, nanos) = system_time_parts(time);
+        write_rfc3339(seconds, nanos)
+    }
+);
+
+#[cfg(test)]
+mod tests {
+    use crate::serialization::EasyConfig;
+    use crate::serialization::tests::parse;
+    use super::*;
+
+    #[test]
+    fn civil_days() {
+        assert_eq!(days_from_civil(1970, 1, 1), 0);
+        assert_eq!(days_from_civil(2000, 3, 1), 11_017);
+        assert_eq!(civil_from_days(-1), (1969, 12, 31));
+        assert_eq!(days_in_month(2024, 2), 29);
+        assert_eq!(days_in_month(2023, 12), 31);
+    }
+
+    #[test]
+    fn system_time() {
+        let epoch = parse::<SystemTime>("1970-01-01T00:00:00Z").unwrap();
+        assert_eq!(epoch, SystemTime::UNIX_EPOCH);
+
+        let time = parse::<SystemTime>("2024-05-01T12:00:00.25+02:00").unwrap();
+        assert_eq!(time, SystemTime::UNIX_EPOCH + Duration::new(1_714_557_600, 250_000_000));
+        assert_eq!(time.serialize().dump(), "2024-05-01T10:00:00.25Z");
+    }
+
+    #[test]
+    fn system_time_round_trip() {
+        for time in [
+            SystemTime::UNIX_EPOCH - Duration::new(86_401, 5),
+            SystemTime::UNIX_EPOCH + Duration::new(4_102_444_800, 1),
+            SystemTime::UNIX_EPOCH + Duration::from_secs(400_000_000_000),
+            SystemTime::UNIX_EPOCH - Duration::from_secs(62_200_000_000),
+        ] {
+            assert_eq!(parse::<SystemTime>(&time.serialize().dump()).unwrap(), time);
+        }
+    }
+
+    #[test]
+    fn years_outside_rfc_3339() {
+        let far = SystemTime::UNIX_EPOCH + Duration::from_secs(400_000_000_000);
+        assert_eq!(far.serialize().dump(), "+14645-06-30T15:06:40Z");
+        let early = parse::<SystemTime>("-0001-12-31T23:59:59Z").unwrap();
+        assert_eq!(early.serialize().dump(), "-0001-12-31T23:59:59Z");
+        assert_eq!(parse::<SystemTime>("0000-01-01T00:00:00Z").unwrap(), early + Duration::from_secs(1));
+    }
+
+    #[test]
+    fn invalid_system_times() {
+        for source in ["12024-05-01T00:00:00Z", "+999999999999-01-01T00:00:00Z", "2024-13-01T00:00:00Z", "2023-02-29T00:00:00Z", "2024-05-01T24:00:00Z", "2024-05-01T10:00:00", "2024-05-01", "later"] {
+            let error = parse::<SystemTime>(source).unwrap_err();
+            assert!(matches!(error.kind(), Kind::InvalidValue { .. }), "{}: {:?}", source, error.kind());
+        }
+    }
+}
@@ -0,0 +1,129 @@
+use ::time::{Date, OffsetDateTime, PrimitiveDateTime, Time, UtcDateTime};
+use ::time::format_description::well_known::Rfc3339;
+use ::time::format_description::BorrowedFormatItem;
+use ::time::macros::format_description;
+
+const DATE: &[BorrowedFormatItem] = format_description!("[year]-[month]-[day]");
+const TIME: &[BorrowedFormatItem] = format_description!("[hour]:[minute]:[second][optional [.[subsecond]]]");
+const WHOLE_TIME: &[BorrowedFormatItem] = format_description!("[hour]:[minute]:[second]");
+const DATE_TIME: &[BorrowedFormatItem] = format_description!("[year]-[month]-[day]T[hour]:[minute]:[second][optional [.[subsecond]]]");
+const WHOLE_DATE_TIME: &[BorrowedFormatItem] = format_description!("[year]-[month]-[day]T[hour]:[minute]:[second]");
+const OFFSET_DATE_TIME: &[BorrowedFormatItem] = format_description!("[year]-[month]-[day]T[hour]:[minute]:[second][optional [.[subsecond]]][offset_hour sign:mandatory]:[offset_minute]:[offset_second]");
+const WHOLE_OFFSET_DATE_TIME: &[BorrowedFormatItem] = format_description!("[year]-[month]-[day]T[hour]:[minute]:[second][offset_hour sign:mandatory]:[offset_minute]:[offset_second]");
+
+// Times are written without a fraction of a second when they don't have one.
+fn write_time(time: &Time) -> String {
+    let format = if time.nanosecond() == 0 { WHOLE_TIME } else { TIME };
+    time.format(format).expect("a time can always be written")
+}
+
+fn write_date_time(date_time: &PrimitiveDateTime) -> String {
+    let format = if date_time.nanosecond() == 0 { WHOLE_DATE_TIME } else { DATE_TIME };
+    date_time.format(format).expect("a date and time can always be written")
+}
+
+// RFC 3339 has no room for years before 0 or offsets with seconds, so dates and times which have
+// either are written with a signed year and the offset's seconds instead.
+fn write_offset_date_time(date_time: &OffsetDateTime) -> String {
+    date_time.format(&Rfc3339).unwrap_or_else(|_| {
+        let format = if date_time.nanosecond() == 0 { WHOLE_OFFSET_DATE_TIME } else { OFFSET_DATE_TIME };
+        date_time.format(format).expect("a date and time can always be written with the offset's seconds")
+    })
+}
+
+fn parse_offset_date_time(text: &str) -> Option<OffsetDateTime> {
+    OffsetDateTime::parse(text, &Rfc3339)
+        .or_else(|_| OffsetDateTime::parse(text, OFFSET_DATE_TIME))
+        .ok()
+}
+
+config_datetime!(
+    OffsetDateTime,
+    "an RFC 3339 date and time such as 2024-05-01T10:00:00+02:00",
+    |text| parse_offset_date_time(text),
+    |time| write_offset_date_time(time)
+);
+config_datetime!(
+    UtcDateTime,
+    "an RFC 3339 date and time such as 2024-05-01T10:00:00Z",
+    |text| parse_offset_date_time(text).map(|x| x.to_utc()),
+    |time| write_offset_date_time(&OffsetDateTime::from(*time))
+);
+config_datetime!(
+    PrimitiveDateTime,
+    "a date and time such as 2024-05-01T10:00:00",
+    |text| PrimitiveDateTime::parse(text, DATE_TIME).ok(),
+    |time| write_date_time(time)
+);
+config_datetime!(
+    Date,
+    "a date such as 2024-05-01",
+    |text| Date::parse(text, DATE).ok(),
+    |date| date.format(DATE).expect("a date can always be written")
+);
+config_datetime!(
+    Time,
+    "a time such as 10:00:00",
+    |text| Time::parse(text, TIME).ok(),
+    |time| write_time(time)
+);
+
+#[cfg(test)]
+mod tests {
+    use ::time::{Duration, Month, UtcOffset};
+    use crate::serialization::EasyConfig;
+    use crate::serialization::tests::parse;
+    use crate::serialization::serialization_error::Kind;
+    use super::*;
+
+    #[test]
+    fn date_times() {
+        let offset = parse::<OffsetDateTime>("2024-05-01T12:00:00.25+02:00").unwrap();
+        assert_eq!(offset.serialize().dump(), "2024-05-01T12:00:00.25+02:00");
+        assert_eq!(parse::<UtcDateTime>("2024-05-01T12:00:00+02:00").unwrap().serialize().dump(), "2024-05-01T10:00:00Z");
+    }
+
+    #[test]
+    fn offsets_with_seconds() {
+        let date_time = PrimitiveDateTime::new(Date::from_calendar_date(2024, Month::May, 1).unwrap(), Time::MIDNIGHT)
+            .assume_offset(UtcOffset::from_hms(1, 0, 30).unwrap());
+        let dumped = date_time.serialize().dump();
+        assert_eq!(dumped, "2024-05-01T00:00:00+01:00:30");
+        assert_eq!(parse::<OffsetDateTime>(&dumped).unwrap(), date_time);
+    }
+
+    #[test]
+    fn years_before_zero() {
+        let date_time = PrimitiveDateTime::new(Date::from_calendar_date(-1, Month::May, 1).unwrap(), Time::MIDNIGHT);
+        let offset = date_time.assume_offset(UtcOffset::from_hms(2, 0, 0).unwrap());
+        let dumped = (offset + Duration::milliseconds(250)).serialize().dump();
+        assert_eq!(dumped, "-0001-05-01T00:00:00.25+02:00:00");
+        assert_eq!(parse::<OffsetDateTime>(&dumped).unwrap(), offset + Duration::milliseconds(250));
+
+        let utc = date_time.as_utc();
+        assert_eq!(parse::<UtcDateTime>(&utc.serialize().dump()).unwrap(), utc);
+        assert_eq!(parse::<PrimitiveDateTime>(&date_time.serialize().dump()).unwrap(), date_time);
+        assert_eq!(parse::<Date>(&date_time.date().serialize().dump()).unwrap(), date_time.date());
+    }
+
+    #[test]
+    fn primitive() {
+        let date = parse::<Date>("2024-02-29").unwrap();
+        assert_eq!(date, Date::from_calendar_date(2024, Month::February, 29).unwrap());
+        assert_eq!(date.serialize().dump(), "2024-02-29");
+
+        let time = parse::<Time>("23:30:05").unwrap();
+        assert_eq!(time.serialize().dump(), "23:30:05");
+        assert_eq!((time + Duration::milliseconds(5)).serialize().dump(), "23:30:05.005");
+
+        let date_time = PrimitiveDateTime::new(date, time);
+        assert_eq!(date_time.serialize().dump(), "2024-02-29T23:30:05");
+        assert_eq!(parse::<PrimitiveDateTime>("2024-02-29T23:30:05").unwrap(), date_time);
+    }
+
+    #[test]
+    fn invalid() {
+        assert!(matches!(parse::<Date>("2023-02-29").unwrap_err().kind(), Kind::InvalidValue { .. }));
+        assert!(matches!(parse::<OffsetDateTime>("2024-05-01T10:00:00").unwrap_err().kind(), Kind::InvalidValue { .. }));
+    }
+}
@@ -0,0 +1,100 @@
+//! Types from other crates, each behind a feature of the same name. Values are read the same way
+//! as strings, so they can be quoted when they contain spaces or `=`, and are checked as they are
+//! loaded so a malformed value is reported at its place in the config.
+
+#[cfg(feature = "regex")]
+use regex::Regex;
+#[cfg(feature = "semver")]
+use semver::{Version, VersionReq};
+#[cfg(feature = "url")]
+use url::Url;
+#[cfg(feature = "uuid")]
+use uuid::Uuid;
+
+macro_rules! config_parsed {
+    ($ty: ty, |$text: ident| $parse: expr) => {
+        impl $crate::serialization::EasyConfig for $ty {
+            fn serialize(&self) -> $crate::expression::Expression {
+                $crate::expression::Expression::presence($crate::serialization::primitives::unquoted_where_possible(self.to_string()))
+            }
+
+            fn deserialize(exprs: &mut $crate::expression_iterator::ExpressionIterator, source_text: impl AsRef<str>) -> Result<Self, $crate::serialization::serialization_error::SerializationError> {
+                use $crate::config_error::Contextualize;
+                let source_text = source_text.as_ref();
+                let span = exprs.peek().map(|x| x.span()).unwrap_or($crate::lexical_span::LexicalSpan::zeros());
+                let text = String::deserialize(exprs, source_text)?;
+                let $text = text.as_str();
+                $parse
+                    .map_err(|e| $crate::serialization::serialization_error::SerializationError::on_span(
+                        $crate::serialization::serialization_error::Kind::Malformed { value: text.clone(), target: stringify!($ty), reason: e.to_string() },
+                        span,
+                        source_text
+                    ))
+                    .contextualize(format!("Error while deserializing a {}", stringify!($ty)))
+            }
+        }
+    };
+}
+
+#[cfg(feature = "url")]
+config_parsed!(Url, |text| Url::parse(text));
+#[cfg(feature = "uuid")]
+config_parsed!(Uuid, |text| Uuid::parse_str(text));
+#[cfg(feature = "semver")]
+config_parsed!(Version, |text| Version::parse(text));
+#[cfg(feature = "semver")]
+config_parsed!(VersionReq, |text| VersionReq::parse(text));
+#[cfg(feature = "regex")]
+config_parsed!(Regex, |text| Regex::new(text));
+
+#[cfg(test)]
+mod tests {
+    use crate::serialization::tests::parse;
+    use crate::serialization::EasyConfig;
+    use super::*;
+
+    #[cfg(feature = "url")]
+    #[test]
+    fn urls() {
+        let url = parse::<Url>("https://example.com/path").unwrap();
+        assert_eq!(url.serialize().dump(), "https://example.com/path");
+
+        let query = Url::parse("https://example.com/search?q=config#top").unwrap();
+        assert_eq!(query.serialize().dump(), "\"https://example.com/search?q=config#top\"");
+        assert_eq!(parse::<Url>(&query.serialize().dump()).unwrap(), query);
+    }
+
+    #[cfg(feature = "uuid")]
+    #[test]
+    fn uuids() {
+        let source = "67e55044-10b1-426f-9247-bb680e5fe0c8";
+        let uuid = parse::<Uuid>(source).unwrap();
+        assert_eq!(uuid.serialize().dump(), source);
+    }
+
+    #[cfg(feature = "semver")]
+    #[test]
+    fn versions() {
+        assert_eq!(parse::<Version>("1.2.3-beta.1").unwrap(), Version::parse("1.2.3-beta.1").unwrap());
+        let requirement = parse::<VersionReq>("\">=1.2, <2\"").unwrap();
+        assert!(requirement.matches(&Version::new(1, 4, 0)));
+        assert_eq!(requirement.serialize().dump(), "\">=1.2, <2\"");
+    }
+
+    #[cfg(feature = "regex")]
+    #[test]
+    fn regexes() {
+        let regex = parse::<Regex>(r#""^[a-z]+\\(\\d\\)$""#).unwrap();
+        assert!(regex.is_match("abc(1)"));
+        assert_eq!(parse::<Regex>(&regex.serialize().dump()).unwrap().as_str(), regex.as_str());
+    }
+
+    #[cfg(feature = "semver")]
+    #[test]
+    fn malformed_values_point_at_the_config() {
+        use crate::serialization::serialization_error::Kind;
+        let error = parse::<Version>("\n  1.2").unwrap_err();
+        assert!(matches!(error.kind(), Kind::Malformed { value, target: "Version", .. } if value == "1.2"));
+        assert!(error.to_string().contains("Error at 2:3"), "{}", error);
+    }
+}
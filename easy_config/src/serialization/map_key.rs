@@ -0,0 +1,107 @@
+use std::path::PathBuf;
+use crate::atom::Atom;
+
+/// Types which can be used as the keys of a map. Maps are written as a list of bindings, so each
+/// key is written as a binding name and parsed back from one. Unit-only enums deriving
+/// `EasyConfig` implement this using their variant names.
+pub trait MapKey: Sized + 'static {
+    /// The binding name this key is written as.
+    fn to_key(&self) -> String;
+    /// Parse a key from a binding name, or `None` if the name isn't a valid key.
+    fn from_key(key: &str) -> Option<Self>;
+}
+
+impl MapKey for String {
+    fn to_key(&self) -> String {
+        self.clone()
+    }
+
+    fn from_key(key: &str) -> Option<Self> {
+        Some(key.to_string())
+    }
+}
+
+macro_rules! key_integer {
+    ($ty: ty) => {
+        impl MapKey for $ty {
+            fn to_key(&self) -> String {
+                self.to_string()
+            }
+
+            fn from_key(key: &str) -> Option<Self> {
+                match Atom::classify(key) {
+                    Atom::Integer(i) => i.convert(),
+                    _ => None,
+                }
+            }
+        }
+    };
+}
+
+key_integer!(i8);
+key_integer!(i16);
+key_integer!(i32);
+key_integer!(i64);
+key_integer!(i128);
+key_integer!(isize);
+
+key_integer!(u8);
+key_integer!(u16);
+key_integer!(u32);
+key_integer!(u64);
+key_integer!(u128);
+key_integer!(usize);
+
+impl MapKey for char {
+    fn to_key(&self) -> String {
+        self.to_string()
+    }
+
+    fn from_key(key: &str) -> Option<Self> {
+        key.parse().ok()
+    }
+}
+
+impl MapKey for bool {
+    fn to_key(&self) -> String {
+        self.to_string()
+    }
+
+    fn from_key(key: &str) -> Option<Self> {
+        match Atom::classify(key) {
+            Atom::Bool(b) => Some(b),
+            _ => None,
+        }
+    }
+}
+
+impl MapKey for PathBuf {
+    fn to_key(&self) -> String {
+        self.to_string_lossy().into_owned()
+    }
+
+    fn from_key(key: &str) -> Option<Self> {
+        Some(PathBuf::from(key))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn integers() {
+        assert_eq!(u16::from_key("8080"), Some(8080));
+        assert_eq!(i8::from_key("-0x10"), Some(-16));
+        assert_eq!(u8::from_key("256"), None);
+        assert_eq!(u8::from_key("port"), None);
+    }
+
+    #[test]
+    fn round_trips() {
+        assert_eq!(char::from_key(&'x'.to_key()), Some('x'));
+        assert_eq!(bool::from_key(&false.to_key()), Some(false));
+        assert_eq!(PathBuf::from_key(&PathBuf::from("/etc/hosts").to_key()), Some(PathBuf::from("/etc/hosts")));
+        assert_eq!(char::from_key("xy"), None);
+    }
+}
@@ -4,13 +4,24 @@ pub mod serialization_error;
 pub mod collections;
 pub mod option_span_combine;
 pub mod option;
+pub mod map_key;
+pub mod wrappers;
+pub mod units;
+pub mod ranges;
+pub mod datetime;
+#[cfg(any(feature = "url", feature = "uuid", feature = "semver", feature = "regex"))]
+pub mod external;
+#[cfg(any(feature = "rust_decimal", feature = "num-bigint"))]
+pub mod big_numbers;
 
 use std::collections::HashMap;
+use std::io::{BufWriter, Write};
 use std::path::Path;
 use crate::expression::Expression;
 use crate::expression_iterator::ExpressionIterator;
 use crate::lexical_span::LexicalSpan;
 use crate::parser::Parser;
+use crate::pretty::PrettyOptions;
 use crate::serialization::serialization_error::{Kind, SerializationError};
 
 pub trait EasyConfig: 'static {
@@ -50,7 +61,9 @@ pub trait DefaultConfig: EasyConfig + Default {
         } else {
             let default = Self::default();
 
-            std::fs::write(path, default.serialize().pretty())?;
+            let mut file = BufWriter::new(std::fs::File::create(path)?);
+            to_writer(&default, &mut file)?;
+            file.flush()?;
 
             Ok((default, LoadMode::Default))
         }
@@ -59,6 +72,17 @@ pub trait DefaultConfig: EasyConfig + Default {
 
 impl<T: Default + EasyConfig> DefaultConfig for T {}
 
+/// Serialize `value` and pretty print it straight into `writer`.
+pub fn to_writer(value: &impl EasyConfig, writer: &mut impl Write) -> std::io::Result<()> {
+    value.serialize().write_pretty(writer)
+}
+
+/// Serialize `value` and pretty print it straight into `writer` with the layout described by
+/// `options`.
+pub fn to_writer_with(value: &impl EasyConfig, writer: &mut impl Write, options: &PrettyOptions) -> std::io::Result<()> {
+    value.serialize().write_pretty_with(writer, options)
+}
+
 
 pub fn deserialize_field_from_map_or_error<T: EasyConfig>(field: impl AsRef<str>, mapping: &mut HashMap<String, Expression>, span: LexicalSpan, source_text: impl AsRef<str>) -> Result<T, SerializationError> {
     let field = field.as_ref();
@@ -75,13 +99,26 @@ pub fn deserialize_field_from_map_or_error<T: EasyConfig>(field: impl AsRef<str>
 
 }
 #[cfg(test)]
-mod tests {
+pub(crate) mod tests {
     use crate::config_error::Contextualize;
     use crate::parser::Parser;
     use super::*;
 
+    // Read a `T` from `source` the way it would be read from a whole config file.
+    pub(crate) fn parse<T: EasyConfig>(source: &str) -> Result<T, SerializationError> {
+        T::deserialize(&mut Parser::new(source).parse().unwrap().into_iter(), source)
+    }
+
+    // Write `value` out and read it back. A value written as a list is read from inside that
+    // list, rather than from the list the whole source forms.
+    pub(crate) fn round_trip<T: EasyConfig>(value: &T) -> T {
+        let text = value.serialize().dump();
+        let parsed = Parser::new(&text).parse().unwrap().into_iter().next().unwrap();
+        T::deserialize(&mut parsed.into_iter(), &text).unwrap()
+    }
+
     #[derive(Debug, PartialEq)]
-    enum Address {
+    pub(crate) enum Address {
         None,
         IpV4(String),
         Index(u32, i32)
@@ -106,7 +143,7 @@ mod tests {
                 .extract_enum(source_text)
                 .contextualize("Unable to deserialize enum Address since we can't extract a discriminant and a argument list")?;
             let span = expression_iterator.span().unwrap();
-            const OPTIONS: &'static [&'static str] = &["None", "IpV4", "Index"];
+            const OPTIONS: &[&str] = &["None", "IpV4", "Index"];
             let mut fields = fields.into_iter();
 
             match discriminant.as_str() {
@@ -129,7 +166,7 @@ mod tests {
     }
     
     #[derive(Debug, PartialEq)]
-    struct Demo {
+    pub(crate) struct Demo {
         name: String,
         addresses: Vec<Address>,
     }
@@ -158,7 +195,7 @@ mod tests {
     }
 
 
-    fn demo() -> Demo {
+    pub(crate) fn demo() -> Demo {
         Demo {
             name: "cat".to_string(),
             addresses: vec![
@@ -169,7 +206,7 @@ mod tests {
         }
     }
 
-    const EXPECTED: &'static str = "(Demo name = cat addresses = (None (IpV4 (127.0.0.1)) (Index (3 -1))))";
+    const EXPECTED: &str = "(Demo name = cat addresses = (None (IpV4 (127.0.0.1)) (Index (3 -1))))";
     #[test]
     fn serialize() {
         let d = demo();
@@ -184,6 +221,13 @@ mod tests {
         )
     }
 
+    #[test]
+    fn to_writer_matches_pretty() {
+        let mut out = Vec::new();
+        to_writer(&demo(), &mut out).unwrap();
+        assert_eq!(String::from_utf8(out).unwrap(), demo().serialize().pretty());
+    }
+
     #[should_panic]
     #[test]
     fn deserialize_err() {
@@ -51,12 +51,20 @@ impl<T: EasyConfig> EasyConfig for Option<T> {
             return Err(SerializationError::on_span(ExpectedList(args), args_span, source_text));
         }
 
-        Ok(Some(T::deserialize(&mut args.into_iter(), source_text)?))
+        // `serialize` wraps the value in a list of its own, the same as an element of a list, so
+        // a lone element is read the way a list reads its elements.
+        let lone = matches!(&args.data, ExpressionData::List(elements, _) if elements.len() == 1);
+        let mut args = args.into_iter();
+        if lone && !T::PASSTHROUGH {
+            return Ok(Some(T::deserialize(&mut args.next().unwrap().into_iter(), source_text)?))
+        }
+        Ok(Some(T::deserialize(&mut args, source_text)?))
     }
 }
 
 #[cfg(test)]
 mod tests {
+    use crate::serialization::tests::{demo, round_trip, Address};
     use super::*;
 
     #[test]
@@ -87,4 +95,28 @@ mod tests {
         let got = Option::deserialize(&mut expected.serialize().into_iter(), "None").unwrap();
         assert_eq!(got, expected);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn vectors() {
+        for value in [Some(vec![1, 2, 3]), Some(vec![1]), Some(vec![]), None] {
+            assert_eq!(round_trip(&value), value);
+        }
+    }
+
+    // A struct is a list of its own, which `serialize` wraps in the list of arguments.
+    #[test]
+    fn structs() {
+        assert_eq!(Some(demo()).serialize().dump(), "(Some ((Demo name = cat addresses = (None (IpV4 (127.0.0.1)) (Index (3 -1))))))");
+        for value in [Some(demo()), None] {
+            assert_eq!(round_trip(&value), value);
+        }
+    }
+
+    // Passthrough types read the argument list themselves, so it is never unwrapped for them.
+    #[test]
+    fn passthrough_enums() {
+        for value in [Some(Address::None), Some(Address::IpV4("127.0.0.1".to_string())), Some(Address::Index(3, -1)), None] {
+            assert_eq!(round_trip(&value), value);
+        }
+    }
+}
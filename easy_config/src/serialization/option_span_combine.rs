@@ -10,7 +10,6 @@ impl OptionSpanCombine for Option<LexicalSpan> {
             *acc = acc.combine(other)
         } else {
             *self = Some(other);
-            return;
         }
     }
 }
\ No newline at end of file
@@ -1,13 +1,36 @@
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+use std::num::IntErrorKind;
+use std::path::PathBuf;
+use crate::atom::Float;
 use crate::config_error::Contextualize;
 use crate::expression::{Atom, Expression, ExpressionData};
 use crate::expression_iterator::ExpressionIterator;
 use crate::lexer;
+use crate::lexer::Lexer;
+use crate::lexer::token::Kind as TokenKind;
+use crate::lexical_span::LexicalSpan;
 use crate::serialization::{EasyConfig};
 use crate::serialization::Kind;
-use crate::serialization::option_span_combine::OptionSpanCombine;
 use crate::serialization::serialization_error::SerializationError;
 
-macro_rules! config {
+// The text an integer was written as, such as `1_000` or `01`, if its span leads to it in
+// `source_text`. Integers built in code have no such text.
+fn integer_literal<'a>(atom: &Atom, span: LexicalSpan, source_text: &'a str) -> Option<&'a str> {
+    let Atom::Integer(_) = atom else { return None };
+    source_text.get(span.start()..span.end()).filter(|x| Atom::classify(*x) == *atom)
+}
+
+// Every primitive is a single presence. Returns its atom along with the span it came from.
+pub(crate) fn next_atom(exprs: &mut ExpressionIterator, source_text: &str) -> Result<(Atom, LexicalSpan), SerializationError> {
+    let expr = exprs.minimized_next_or_err(source_text)?;
+    let span = expr.span();
+    match expr.data {
+        ExpressionData::Presence(atom, _) => Ok((atom, span)),
+        _ => Err(SerializationError::on_span(Kind::ExpectedPresence(expr), span, source_text))
+    }
+}
+
+macro_rules! config_integer {
     ($ty: ty) => {
         impl EasyConfig for $ty {
             fn serialize(&self) -> Expression {
@@ -16,98 +39,208 @@ macro_rules! config {
 
             fn deserialize(exprs: &mut ExpressionIterator, source_text: impl AsRef<str>) -> Result<Self, SerializationError> {
                 let source_text = source_text.as_ref();
-                let expr = exprs.minimized_next_or_err(source_text)?;
-                let span = expr.span();
-                match expr.data {
-                    ExpressionData::Presence(p, _) => match p {
-                        Atom::Number(n) => Ok(n.parse()?),
-                        _ => Err(SerializationError::on_span(Kind::ExpectedNumber(p.to_string()), span, source_text))
-                        .contextualize(format!("Error while deserializing a {}", stringify!($ty))),
+                let out_of_range = |literal: String, span| SerializationError::on_span(
+                    Kind::IntegerOutOfRange {
+                        literal,
+                        target: stringify!($ty),
+                        range: format!("{} to {}", <$ty>::MIN, <$ty>::MAX),
                     },
-                    _ => Err(SerializationError::on_span(Kind::ExpectedPresence(expr), span, source_text))
-                    .contextualize(format!("Error while deserializing a {}", stringify!($ty)))
-                }
+                    span,
+                    source_text
+                );
+
+                let (atom, span) = next_atom(exprs, source_text)?;
+                match atom {
+                    Atom::Integer(i) => i.convert().ok_or_else(|| out_of_range(i.to_string(), span)),
+                    // Integers too big for a u128 are left as raw numbers by the parser.
+                    Atom::Number(n) => match n.parse::<$ty>() {
+                        Ok(value) => Ok(value),
+                        Err(e) if matches!(e.kind(), IntErrorKind::PosOverflow | IntErrorKind::NegOverflow) => Err(out_of_range(n, span)),
+                        Err(_) => Err(SerializationError::on_span(Kind::ExpectedInteger(n), span, source_text)),
+                    },
+                    other => Err(SerializationError::on_span(Kind::ExpectedInteger(other.to_string()), span, source_text)),
+                }.contextualize(format!("Error while deserializing a {}", stringify!($ty)))
             }
         }
     };
+}
 
-    ($ty: ty, non_numeric) => {
+macro_rules! config_float {
+    ($ty: ty, $from_integer: ident, $from_float: expr) => {
         impl EasyConfig for $ty {
             fn serialize(&self) -> Expression {
-                Expression::presence(self.to_string())
+                Expression::presence(*self)
             }
 
             fn deserialize(exprs: &mut ExpressionIterator, source_text: impl AsRef<str>) -> Result<Self, SerializationError> {
                 let source_text = source_text.as_ref();
-                let expr = exprs.minimized_next_or_err(source_text)?;
-                let span = expr.span();
-                match expr.data {
-                    ExpressionData::Presence(p, _) => match p {
-                        Atom::Text(t) => Ok(t.parse()?),
-                        _ => Err(SerializationError::on_span(Kind::ExpectedText(p.to_string()), span, source_text))
-                        .contextualize(format!("Error while deserializing a {}", stringify!($ty))),
-                    },
-                    _ => Err(SerializationError::on_span(Kind::ExpectedPresence(expr), span, source_text))
-                    .contextualize(format!("Error while deserializing a {}", stringify!($ty)))
-                }
+                let (atom, span) = next_atom(exprs, source_text)?;
+                match atom {
+                    Atom::Float(f) => Ok($from_float(&f)),
+                    Atom::Integer(i) => Ok(i.$from_integer()),
+                    other => Err(SerializationError::on_span(Kind::ExpectedNumber(other.to_string()), span, source_text)),
+                }.contextualize(format!("Error while deserializing a {}", stringify!($ty)))
             }
         }
     };
 }
 
-config!(i8);
-config!(i16);
-config!(i32);
-config!(i64);
-config!(i128);
-config!(isize);
+config_integer!(i8);
+config_integer!(i16);
+config_integer!(i32);
+config_integer!(i64);
+config_integer!(i128);
+config_integer!(isize);
+
+config_integer!(u8);
+config_integer!(u16);
+config_integer!(u32);
+config_integer!(u64);
+config_integer!(u128);
+config_integer!(usize);
 
-config!(u8);
-config!(u16);
-config!(u32);
-config!(u64);
-config!(u128);
-config!(usize);
+config_float!(f32, to_f32, Float::to_f32);
+config_float!(f64, to_f64, Float::value);
+
+impl EasyConfig for bool {
+    fn serialize(&self) -> Expression {
+        Expression::presence(*self)
+    }
+
+    fn deserialize(exprs: &mut ExpressionIterator, source_text: impl AsRef<str>) -> Result<Self, SerializationError> {
+        let source_text = source_text.as_ref();
+        let (atom, span) = next_atom(exprs, source_text)?;
+        match atom {
+            Atom::Bool(b) => Ok(b),
+            other => Err(SerializationError::on_span(Kind::ExpectedBool(other.to_string()), span, source_text)),
+        }.contextualize("Error while deserializing a bool")
+    }
+}
 
-config!(f32);
-config!(f64);
+/// A `bool` which also accepts the spellings people tend to write in hand edited files: `yes` and
+/// `no`, `on` and `off`, `1` and `0`, in any case. A plain `bool` only accepts `true` and `false`.
+/// It is always written back as `true` or `false`.
+#[derive(Debug, Copy, Clone, Default, Ord, PartialOrd, Eq, PartialEq, Hash)]
+pub struct LenientBool(pub bool);
 
-config!(bool, non_numeric);
-config!(char, non_numeric);
+impl LenientBool {
+    pub fn value(&self) -> bool {
+        self.0
+    }
 
-fn escape_string(text: impl AsRef<str>) -> String {
-    let mut escaped = String::with_capacity(text.as_ref().len());
-    for ch in text.as_ref().chars() {
-        if lexer::STOPPING_CHARS.contains(&ch) {
-            escaped.push('\\');
+    fn parse(text: &str) -> Option<Self> {
+        match text.to_ascii_lowercase().as_str() {
+            "true" | "yes" | "on" | "1" => Some(LenientBool(true)),
+            "false" | "no" | "off" | "0" => Some(LenientBool(false)),
+            _ => None,
         }
-        escaped.push(ch);
     }
-    escaped
 }
 
+impl From<bool> for LenientBool {
+    fn from(value: bool) -> Self {
+        LenientBool(value)
+    }
+}
+
+impl From<LenientBool> for bool {
+    fn from(value: LenientBool) -> Self {
+        value.0
+    }
+}
+
+impl EasyConfig for LenientBool {
+    fn serialize(&self) -> Expression {
+        self.0.serialize()
+    }
+
+    fn deserialize(exprs: &mut ExpressionIterator, source_text: impl AsRef<str>) -> Result<Self, SerializationError> {
+        let source_text = source_text.as_ref();
+        let (atom, span) = next_atom(exprs, source_text)?;
+        // Only `1` and `0` themselves count, not other integers equal to them such as `01`.
+        let text = integer_literal(&atom, span, source_text).map(str::to_string).unwrap_or_else(|| atom.to_text());
+        LenientBool::parse(&text)
+            .ok_or_else(|| SerializationError::on_span(
+                Kind::InvalidValue { value: text, expected: "one of true, yes, on, 1, false, no, off or 0" },
+                span,
+                source_text
+            ))
+            .contextualize("Error while deserializing a LenientBool")
+    }
+}
+
+impl EasyConfig for char {
+    fn serialize(&self) -> Expression {
+        Expression::presence(Atom::string(self.to_string()))
+    }
+
+    fn deserialize(exprs: &mut ExpressionIterator, source_text: impl AsRef<str>) -> Result<Self, SerializationError> {
+        let source_text = source_text.as_ref();
+        let (atom, span) = next_atom(exprs, source_text)?;
+        atom.to_text()
+            .parse()
+            .map_err(|e| SerializationError::on_span(Kind::ParseCharError(e), span, source_text))
+            .contextualize("Error while deserializing a char")
+    }
+}
+
+// The whitespace written between two words, without any comments in it. Words which weren't read
+// from `source_text` next to each other, such as those built in code, are joined by a space.
+fn whitespace_between(previous: LexicalSpan, next: LexicalSpan, source_text: &str) -> String {
+    let Some(gap) = source_text.get(previous.end()..next.start()).filter(|x| !x.is_empty()) else {
+        return " ".to_string()
+    };
+
+    let mut whitespace = String::new();
+    let mut written = 0;
+    for token in Lexer::lossless(gap) {
+        if token.kind() != TokenKind::Comment {
+            return " ".to_string()
+        }
+        whitespace.push_str(&gap[written..token.span().start()]);
+        written = token.span().end();
+    }
+    whitespace.push_str(&gap[written..]);
+    whitespace
+}
 
 fn deserialize_string(exprs: &mut ExpressionIterator, source_text: &str) -> Result<String, SerializationError> {
-    let mut span = None;
-    for expr in exprs {
-        span.combine(expr.span());
+    // A quoted string already holds exactly the text it stands for.
+    if let Some(ExpressionData::Presence(Atom::Quoted(text), _)) = exprs.peek().map(|x| &x.data) {
+        let text = text.clone();
+        exprs.next();
+        return Ok(text)
+    }
 
-        let ExpressionData::Presence(_, _) = &expr.data else {
-            return Err(SerializationError::on_span(Kind::ExpectedPresence(expr), span.unwrap(), source_text))
+    // Several words are joined by the whitespace written between them, while each word has its
+    // escapes decoded.
+    let mut text = String::new();
+    let mut previous: Option<LexicalSpan> = None;
+    for expr in exprs {
+        let ExpressionData::Presence(atom, span) = &expr.data else {
+            let span = previous.map(|x| x.combine(expr.span())).unwrap_or(expr.span());
+            return Err(SerializationError::on_span(Kind::ExpectedPresence(expr), span, source_text))
                 .contextualize("Error while deserializing a String");
         };
+
+        if let Some(previous) = previous {
+            text.push_str(&whitespace_between(previous, *span, source_text));
+        }
+        // Integers are kept as they were written, so `1_000` doesn't become `1000`.
+        match integer_literal(atom, *span, source_text) {
+            Some(written) => text.push_str(written),
+            None => text.push_str(&atom.to_text()),
+        }
+        previous = Some(*span);
     }
 
-    let span = span.unwrap();
-    Ok(source_text[span.start()..span.end()].to_string())
+    Ok(text)
 }
 impl EasyConfig for String {
     fn serialize(&self) -> Expression {
-        Expression::list(
-            self.split_whitespace()
-                .map(|x| Expression::presence(escape_string(x)))
-                .collect::<Vec<Expression>>(),
-        ).minimized()
+        // Anything that wouldn't read back as exactly this text, such as runs of whitespace or a
+        // backslash, is quoted.
+        Expression::presence(Atom::string(self.clone()))
     }
 
     fn deserialize(exprs: &mut ExpressionIterator, source_text: impl AsRef<str>) -> Result<Self, SerializationError>
@@ -129,11 +262,179 @@ impl EasyConfig for String {
     }
 }
 
+// Values such as `127.0.0.1` look like numbers, but are kept as they were written, so they can
+// be left unquoted.
+pub(crate) fn unquoted_where_possible(text: String) -> Atom {
+    match Atom::classify(&text) {
+        Atom::Number(n) if !n.contains(|c: char| c.is_whitespace() || c == '\\' || c == '"' || lexer::STOPPING_CHARS.contains(&c)) => Atom::Number(n),
+        _ => Atom::string(text),
+    }
+}
+
+macro_rules! config_from_str {
+    ($ty: ty, $expected: literal) => {
+        impl EasyConfig for $ty {
+            fn serialize(&self) -> Expression {
+                Expression::presence(unquoted_where_possible(self.to_string()))
+            }
+
+            fn deserialize(exprs: &mut ExpressionIterator, source_text: impl AsRef<str>) -> Result<Self, SerializationError> {
+                let source_text = source_text.as_ref();
+                let (atom, span) = next_atom(exprs, source_text)?;
+                atom.to_text()
+                    .parse()
+                    .map_err(|_| SerializationError::on_span(Kind::InvalidValue { value: atom.to_string(), expected: $expected }, span, source_text))
+                    .contextualize(format!("Error while deserializing a {}", stringify!($ty)))
+            }
+        }
+    };
+}
+
+config_from_str!(IpAddr, "an IP address, such as 127.0.0.1 or ::1");
+config_from_str!(Ipv4Addr, "an IPv4 address, such as 127.0.0.1");
+config_from_str!(Ipv6Addr, "an IPv6 address, such as ::1");
+config_from_str!(SocketAddr, "a socket address, such as 127.0.0.1:8080 or [::1]:8080");
+
+// Paths are read the same way as strings, so they can contain spaces. Anything that isn't a
+// single plain word is quoted when written, which keeps backslashes and spaces intact.
+impl EasyConfig for PathBuf {
+    fn serialize(&self) -> Expression {
+        Expression::presence(Atom::string(self.to_string_lossy()))
+    }
+
+    fn deserialize(exprs: &mut ExpressionIterator, source_text: impl AsRef<str>) -> Result<Self, SerializationError> {
+        String::deserialize(exprs, source_text)
+            .map(PathBuf::from)
+            .contextualize("Error while deserializing a PathBuf")
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::parser::Parser;
+    use crate::serialization::tests::{parse, round_trip};
     use super::*;
 
+    #[test]
+    fn integers() {
+        assert_eq!(parse::<u8>("255").unwrap(), 255);
+        assert_eq!(parse::<i32>("-0x10").unwrap(), -16);
+        assert_eq!(parse::<u32>("1_000_000").unwrap(), 1_000_000);
+        assert_eq!(parse::<i128>(&i128::MIN.to_string()).unwrap(), i128::MIN);
+    }
+
+    #[test]
+    fn integer_out_of_range() {
+        let error = parse::<u8>("256").unwrap_err();
+        let Kind::IntegerOutOfRange { literal, target, range } = error.kind() else {
+            panic!("expected an out of range error, but got {:?}", error.kind())
+        };
+        assert_eq!((literal.as_str(), *target, range.as_str()), ("256", "u8", "0 to 255"));
+        assert!(error.to_string().contains("Error at 1:1"));
+    }
+
+    #[test]
+    fn out_of_range_error_points_at_literal() {
+        let error = parse::<i8>("\n  -129").unwrap_err();
+        assert!(matches!(error.kind(), Kind::IntegerOutOfRange { .. }));
+        assert!(error.to_string().contains("Error at 2:3"));
+    }
+
+    #[test]
+    fn huge_integer_out_of_range() {
+        let error = parse::<u128>("340282366920938463463374607431768211456").unwrap_err();
+        assert!(matches!(error.kind(), Kind::IntegerOutOfRange { .. }));
+    }
+
+    #[test]
+    fn floats() {
+        assert_eq!(parse::<f64>("1.5").unwrap(), 1.5);
+        assert_eq!(parse::<f64>("2").unwrap(), 2.0);
+        assert_eq!(parse::<f32>("0.1").unwrap(), 0.1_f32);
+        assert!(matches!(parse::<f64>("abc").unwrap_err().kind(), Kind::ExpectedNumber(_)));
+    }
+
+    #[test]
+    fn special_floats() {
+        assert!(parse::<f64>("nan").unwrap().is_nan());
+        assert_eq!(parse::<f32>("-inf").unwrap(), f32::NEG_INFINITY);
+        assert!(parse::<f64>("-0.0").unwrap().is_sign_negative());
+        assert_eq!(f64::INFINITY.serialize().dump(), "inf");
+        assert_eq!(round_trip(&f64::MIN_POSITIVE), f64::MIN_POSITIVE);
+    }
+
+    #[test]
+    fn float_is_not_an_integer() {
+        assert!(matches!(parse::<u8>("1.5").unwrap_err().kind(), Kind::ExpectedInteger(_)));
+    }
+
+    #[test]
+    fn bools() {
+        assert!(parse::<bool>("true").unwrap());
+        assert!(!parse::<bool>("false").unwrap());
+        assert!(matches!(parse::<bool>("yes").unwrap_err().kind(), Kind::ExpectedBool(_)));
+    }
+
+    #[test]
+    fn lenient_bools() {
+        for text in ["true", "Yes", "ON", "1", "\"yes\""] {
+            assert_eq!(parse::<LenientBool>(text).unwrap(), LenientBool(true), "{}", text);
+        }
+        for text in ["false", "No", "off", "0"] {
+            assert_eq!(parse::<LenientBool>(text).unwrap(), LenientBool(false), "{}", text);
+        }
+        assert_eq!(LenientBool(true).serialize().dump(), "true");
+
+        for text in ["01", "00", "0x1", "+1", "-0", "1_0"] {
+            assert!(parse::<LenientBool>(text).is_err(), "{}", text);
+        }
+        let built = Expression::list(vec![Expression::presence(1)]);
+        assert_eq!(LenientBool::deserialize(&mut built.into_iter(), "unrelated").unwrap(), LenientBool(true));
+
+        let error = parse::<LenientBool>("maybe").unwrap_err();
+        assert!(matches!(error.kind(), Kind::InvalidValue { value, .. } if value == "maybe"));
+        assert!(error.to_string().contains("one of true, yes, on, 1, false, no, off or 0"), "{}", error);
+    }
+
+    #[test]
+    fn char_round_trip() {
+        for c in ['a', '7', '(', ' ', '"'] {
+            let dumped = c.serialize().dump();
+            assert_eq!(parse::<char>(&dumped).unwrap(), c);
+        }
+    }
+
+    #[test]
+    fn addresses() {
+        let v4: Ipv4Addr = "127.0.0.1".parse().unwrap();
+        let socket: SocketAddr = "[::1]:8080".parse().unwrap();
+        assert_eq!(v4.serialize().dump(), "127.0.0.1");
+        assert_eq!(socket.serialize().dump(), "[::1]:8080");
+        assert_eq!(round_trip(&v4), v4);
+        assert_eq!(round_trip(&socket), socket);
+        assert_eq!(round_trip(&IpAddr::from(Ipv6Addr::LOCALHOST)), IpAddr::from(Ipv6Addr::LOCALHOST));
+        assert_eq!(parse::<Ipv6Addr>("2001:db8::1").unwrap(), "2001:db8::1".parse::<Ipv6Addr>().unwrap());
+    }
+
+    #[test]
+    fn invalid_address() {
+        assert!(matches!(parse::<Ipv4Addr>("256.0.0.1").unwrap_err().kind(), Kind::InvalidValue { .. }));
+
+        let error = parse::<SocketAddr>("localhost").unwrap_err();
+        assert!(matches!(error.kind(), Kind::InvalidValue { value, .. } if value == "localhost"));
+        assert!(error.to_string().contains("such as 127.0.0.1:8080"), "{}", error);
+    }
+
+    #[test]
+    fn paths() {
+        for path in ["src/content", "/home/me/My Documents", r"C:\Program Files\app (x86)", "a#b", ""] {
+            let path = PathBuf::from(path);
+            assert_eq!(round_trip(&path), path);
+        }
+        assert_eq!(PathBuf::from("src/content").serialize().dump(), "src/content");
+        assert_eq!(parse::<PathBuf>("my file.txt").unwrap(), PathBuf::from("my file.txt"));
+    }
+
     #[test]
     fn serialize_string() {
         let expected = "hi".to_string();
@@ -142,11 +443,7 @@ mod tests {
     #[test]
     fn serialize_string_with_space() {
         let expected = "hi there".to_string();
-        assert_eq!(expected.serialize(), Expression::list(
-    vec![
-            Expression::presence("hi"),
-            Expression::presence("there")
-        ]))
+        assert_eq!(expected.serialize(), Expression::presence(Atom::Quoted(expected.clone())))
     }
 
     #[test]
@@ -165,6 +462,11 @@ mod tests {
         assert_eq!(got, "hi there")
     }
 
+    #[test]
+    fn deserialize_quoted_string() {
+        assert_eq!(parse::<String>("\"a (b) \\\" c\"").unwrap(), "a (b) \" c");
+    }
+
     #[test]
     fn deserialize_bound_string_with_space() {
         let content = "hi there";
@@ -178,4 +480,89 @@ mod tests {
         let got = String::deserialize(&mut b.value.into_iter(), &source).unwrap();
         assert_eq!(got, content)
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn escapes_are_decoded() {
+        assert_eq!(parse::<String>(r"\(a\)").unwrap(), "(a)");
+        assert_eq!(parse::<String>(r"(hello  \(world\) 1_000)").unwrap(), "hello  (world) 1_000");
+        assert_eq!(parse::<char>(r"\#").unwrap(), '#');
+        assert_eq!(parse::<PathBuf>(r"C:\\dir\ name").unwrap(), PathBuf::from(r"C:\dir name"));
+    }
+
+    #[test]
+    fn comments_between_words_are_dropped() {
+        assert_eq!(parse::<String>("(hello # c\n world)").unwrap(), "hello \n world");
+        assert_eq!(parse::<String>("(a #x\n#y\nb)").unwrap(), "a \n\nb");
+    }
+
+    #[test]
+    fn words_built_in_code_are_joined_by_spaces() {
+        let words = Expression::list(vec![Expression::presence("hi"), Expression::presence(1_000), Expression::presence("there")]);
+        assert_eq!(String::deserialize(&mut words.into_iter(), "").unwrap(), "hi 1000 there");
+        // Spans which point elsewhere in the source aren't used either.
+        let source = "one two";
+        let words = Expression::list(vec![
+            Expression::presence("b").with_span(LexicalSpan::new(4, 7)),
+            Expression::presence(2).with_span(LexicalSpan::new(0, 3)),
+        ]);
+        assert_eq!(String::deserialize(&mut words.into_iter(), source).unwrap(), "b 2");
+    }
+
+    #[test]
+    fn strings_keep_their_whitespace_and_backslashes() {
+        for text in ["a  b", "  leading", "trailing\t", "two\nlines", r"C:\dir", "a#b", "-x", "12", "true", ""] {
+            assert_eq!(round_trip(&text.to_string()), text);
+        }
+    }
+
+    proptest::proptest! {
+        // Every bit pattern is tried, so subnormals, both zeros and every NaN are covered. A NaN
+        // only has to come back as a NaN.
+        #[test]
+        fn any_f64_round_trips(bits in proptest::prelude::any::<u64>()) {
+            let value = f64::from_bits(bits);
+            let text = value.serialize().dump();
+            let read = parse::<f64>(&text).unwrap();
+            proptest::prop_assert!(read.to_bits() == bits || (value.is_nan() && read.is_nan()), "{} read back as {}", text, read);
+        }
+
+        #[test]
+        fn any_f32_round_trips(bits in proptest::prelude::any::<u32>()) {
+            let value = f32::from_bits(bits);
+            let text = value.serialize().dump();
+            let read = parse::<f32>(&text).unwrap();
+            proptest::prop_assert!(read.to_bits() == bits || (value.is_nan() && read.is_nan()), "{} read back as {}", text, read);
+        }
+
+        // `{:e}` writes the fewest significant digits which read back as the same value.
+        #[test]
+        fn floats_are_written_as_short_as_possible(value in proptest::num::f64::NORMAL | proptest::num::f64::SUBNORMAL) {
+            let significant = |x: &str| x.chars()
+                .take_while(|c| *c != 'e')
+                .filter(char::is_ascii_digit)
+                .collect::<String>()
+                .trim_matches('0')
+                .to_string();
+            let text = value.serialize().dump();
+            proptest::prop_assert_eq!(significant(&text), significant(&format!("{:e}", value)), "{}", text);
+        }
+
+        #[test]
+        fn any_string_round_trips(text in proptest::prelude::any::<String>()) {
+            proptest::prop_assert_eq!(round_trip(&text), text);
+        }
+
+        // Arbitrary strings rarely hit the characters the lexer cares about, so these are tried
+        // far more often here.
+        #[test]
+        fn awkward_strings_round_trip(text in r#"[ \t\n\r\\#()="'a-z0-9.+_-]{0,12}"#) {
+            proptest::prop_assert_eq!(round_trip(&text), text.clone());
+
+            let source = Expression::list(vec![Expression::binding("text", text.serialize())]).pretty();
+            let parsed = Parser::new(&source).parse().unwrap().into_iter().next().unwrap();
+            let mut bindings = parsed.into_iter();
+            let binding = bindings.find_binding("text", &source).unwrap();
+            proptest::prop_assert_eq!(String::deserialize(&mut binding.value.into_iter(), &source).unwrap(), text);
+        }
+    }
+}
@@ -0,0 +1,356 @@
+//! Ranges are written with Rust's syntax as a single token, such as `8000..8100`, `1..=5`,
+//! `8000..` or `..=5`. Ranges with both ends can also be written as a list of the two ends, as in
+//! `(8000 8100)`, and ranges with one end as a list of the end and the operator, as in
+//! `("a b" ..)` or `(..= "a b")`. Ends which can't be part of a single token are written that way.
+
+use std::ops::{Range, RangeFrom, RangeInclusive, RangeTo, RangeToInclusive};
+use crate::config_error::Contextualize;
+use crate::expression::{Atom, Expression, ExpressionData};
+use crate::expression_iterator::ExpressionIterator;
+use crate::lexer::Lexer;
+use crate::lexical_span::LexicalSpan;
+use crate::serialization::EasyConfig;
+use crate::serialization::serialization_error::{Kind, SerializationError};
+
+// One end of a range written as a single token, along with where it is in the source.
+struct End {
+    text: String,
+    span: LexicalSpan,
+}
+
+impl End {
+    fn deserialize<T: EasyConfig>(self, source_text: &str) -> Result<T, SerializationError> {
+        let end = Expression::presence(Atom::classify(&self.text)).with_span(self.span);
+        T::deserialize(&mut end.into_iter(), source_text)
+    }
+}
+
+struct WrittenRange {
+    text: String,
+    span: LexicalSpan,
+    start: Option<End>,
+    end: Option<End>,
+    inclusive: bool,
+}
+
+// Where a range written as one token splits into its ends: the end of its start, whether it is
+// inclusive, and the start of its end.
+fn split(text: &str) -> Option<(usize, bool, usize)> {
+    let split = text.find("..")?;
+    let inclusive = text[split..].starts_with("..=");
+    Some((split, inclusive, split + if inclusive { 3 } else { 2 }))
+}
+
+// Take the next expression if it is a range written as one token. In the list form, the token is
+// the only element, so an end which happens to contain `..` isn't taken for the whole range.
+fn written_range(exprs: &mut ExpressionIterator) -> Option<WrittenRange> {
+    if exprs.len() != 1 {
+        return None
+    }
+    let text = match &exprs.peek()?.data {
+        ExpressionData::Presence(Atom::Text(text) | Atom::Number(text), _) if text.contains("..") => text.clone(),
+        _ => return None,
+    };
+    let span = exprs.next()?.span();
+
+    let (split, inclusive, after) = split(&text).expect("the text contains ..");
+    let end = |from: usize, to: usize| (from < to).then(|| End {
+        text: text[from..to].to_string(),
+        span: LexicalSpan::new(span.start() + from, span.start() + to),
+    });
+
+    Some(WrittenRange {
+        start: end(0, split),
+        end: end(after, text.len()),
+        text,
+        span,
+        inclusive,
+    })
+}
+
+fn unexpected(text: String, span: LexicalSpan, source_text: &str, expected: &'static str) -> SerializationError {
+    SerializationError::on_span(Kind::InvalidValue { value: text, expected }, span, source_text)
+}
+
+// Read a range with both ends, from either form.
+fn deserialize_ends<T: EasyConfig + PartialOrd>(exprs: &mut ExpressionIterator, source_text: &str, inclusive: bool, expected: &'static str) -> Result<(T, T), SerializationError> {
+    let (start, end, span) = match written_range(exprs) {
+        Some(WrittenRange { start: Some(start), end: Some(end), inclusive: i, span, .. }) if i == inclusive => {
+            (start.deserialize::<T>(source_text)?, end.deserialize::<T>(source_text)?, span)
+        }
+        Some(range) => return Err(unexpected(range.text, range.span, source_text, expected)),
+        None => {
+            let start = exprs.deserialize_next::<T>(source_text)?;
+            let end = exprs.deserialize_next::<T>(source_text)?;
+            (start, end, exprs.span().unwrap_or(LexicalSpan::zeros()))
+        }
+    };
+
+    if start > end {
+        return Err(SerializationError::on_span(
+            Kind::InvertedRange { start: start.serialize().dump(), end: end.serialize().dump() },
+            span,
+            source_text
+        ))
+    }
+    Ok((start, end))
+}
+
+// Take the next expression if it is `operator` written on its own.
+fn eat_operator(exprs: &mut ExpressionIterator, operator: &str) -> bool {
+    let is_operator = exprs.peek().is_some_and(|x| matches!(
+        &x.data,
+        ExpressionData::Presence(Atom::Text(text) | Atom::Quoted(text), _) if text == operator
+    ));
+    if is_operator {
+        exprs.next();
+    }
+    is_operator
+}
+
+// Read a range with only one end, from either form. Returns that end.
+fn deserialize_open<T: EasyConfig>(exprs: &mut ExpressionIterator, source_text: &str, has_start: bool, inclusive: bool, expected: &'static str) -> Result<T, SerializationError> {
+    let expr = exprs.peek().cloned();
+    let Some(range) = written_range(exprs) else {
+        let operator = if inclusive { "..=" } else { ".." };
+        if exprs.len() == 2 {
+            if has_start {
+                let start = exprs.deserialize_next::<T>(source_text)?;
+                if eat_operator(exprs, operator) {
+                    return Ok(start)
+                }
+            } else if eat_operator(exprs, operator) {
+                return exprs.deserialize_next::<T>(source_text)
+            }
+        }
+
+        let (text, span) = match expr {
+            Some(expr) => (expr.dump(), expr.span()),
+            None => return Err(SerializationError::end_of_input(source_text)),
+        };
+        return Err(unexpected(text, span, source_text, expected))
+    };
+
+    match (range.start, range.end) {
+        (Some(start), None) if has_start && !range.inclusive => start.deserialize(source_text),
+        (None, Some(end)) if !has_start && range.inclusive == inclusive => end.deserialize(source_text),
+        _ => Err(unexpected(range.text, range.span, source_text, expected)),
+    }
+}
+
+// The atom of one end, if it might be written as part of a single token.
+fn end_atom(end: &impl EasyConfig) -> Option<Atom> {
+    match end.serialize().minimized().data {
+        ExpressionData::Presence(atom @ (Atom::Text(_) | Atom::Number(_) | Atom::Integer(_) | Atom::Float(_)), _) => Some(atom),
+        _ => None,
+    }
+}
+
+// The range written as one token, if reading that token back gives the same ends. Ends such as
+// `inf` or `a.` would otherwise be split differently, or not lexed as one token at all.
+fn single_token(start: Option<&Atom>, end: Option<&Atom>, operator: &str) -> Option<Atom> {
+    let written = |end: Option<&Atom>| end.map(|x| x.to_string()).unwrap_or_default();
+    let text = format!("{}{}{}", written(start), operator, written(end));
+    let mut tokens = Lexer::new(&text);
+    if tokens.next()?.lexeme() != text || tokens.next().is_some() {
+        return None
+    }
+
+    let atom = Atom::from_token(&text);
+    let (Atom::Text(written) | Atom::Number(written)) = &atom else { return None };
+    let (before, inclusive, after) = split(written)?;
+    let reads_back = |text: &str, end: Option<&Atom>| match end {
+        Some(end) => Atom::classify(text) == *end,
+        None => text.is_empty(),
+    };
+    let same = inclusive == (operator == "..=") &&
+        reads_back(&written[..before], start) &&
+        reads_back(&written[after..], end);
+    same.then_some(atom)
+}
+
+fn serialize_ends(start: &impl EasyConfig, end: &impl EasyConfig, operator: &str) -> Expression {
+    let token = end_atom(start).zip(end_atom(end)).and_then(|(start, end)| single_token(Some(&start), Some(&end), operator));
+    match token {
+        Some(atom) => Expression::presence(atom),
+        None => Expression::list(vec![start.serialize(), end.serialize()]),
+    }
+}
+
+// Write a range with only one end, as one token if the end can be part of one.
+fn serialize_open(bound: &impl EasyConfig, has_start: bool, operator: &str) -> Expression {
+    let atom = end_atom(bound);
+    let token = atom.as_ref().and_then(|atom| match has_start {
+        true => single_token(Some(atom), None, operator),
+        false => single_token(None, Some(atom), operator),
+    });
+    if let Some(token) = token {
+        return Expression::presence(token)
+    }
+
+    let operator = Expression::presence(Atom::string(operator));
+    match has_start {
+        true => Expression::list(vec![bound.serialize(), operator]),
+        false => Expression::list(vec![operator, bound.serialize()]),
+    }
+}
+
+impl<T: EasyConfig + PartialOrd> EasyConfig for Range<T> {
+    fn serialize(&self) -> Expression {
+        serialize_ends(&self.start, &self.end, "..")
+    }
+
+    fn deserialize(exprs: &mut ExpressionIterator, source_text: impl AsRef<str>) -> Result<Self, SerializationError> {
+        let (start, end) = deserialize_ends(exprs, source_text.as_ref(), false, "a range such as 8000..8100")
+            .contextualize("Error while deserializing a Range")?;
+        Ok(start..end)
+    }
+}
+
+impl<T: EasyConfig + PartialOrd> EasyConfig for RangeInclusive<T> {
+    fn serialize(&self) -> Expression {
+        serialize_ends(self.start(), self.end(), "..=")
+    }
+
+    fn deserialize(exprs: &mut ExpressionIterator, source_text: impl AsRef<str>) -> Result<Self, SerializationError> {
+        let (start, end) = deserialize_ends(exprs, source_text.as_ref(), true, "a range such as 1..=5")
+            .contextualize("Error while deserializing a RangeInclusive")?;
+        Ok(start..=end)
+    }
+}
+
+impl<T: EasyConfig> EasyConfig for RangeFrom<T> {
+    fn serialize(&self) -> Expression {
+        serialize_open(&self.start, true, "..")
+    }
+
+    fn deserialize(exprs: &mut ExpressionIterator, source_text: impl AsRef<str>) -> Result<Self, SerializationError> {
+        let start = deserialize_open(exprs, source_text.as_ref(), true, false, "a range such as 8000..")
+            .contextualize("Error while deserializing a RangeFrom")?;
+        Ok(start..)
+    }
+}
+
+impl<T: EasyConfig> EasyConfig for RangeTo<T> {
+    fn serialize(&self) -> Expression {
+        serialize_open(&self.end, false, "..")
+    }
+
+    fn deserialize(exprs: &mut ExpressionIterator, source_text: impl AsRef<str>) -> Result<Self, SerializationError> {
+        let end = deserialize_open(exprs, source_text.as_ref(), false, false, "a range such as ..8100")
+            .contextualize("Error while deserializing a RangeTo")?;
+        Ok(..end)
+    }
+}
+
+impl<T: EasyConfig> EasyConfig for RangeToInclusive<T> {
+    fn serialize(&self) -> Expression {
+        serialize_open(&self.end, false, "..=")
+    }
+
+    fn deserialize(exprs: &mut ExpressionIterator, source_text: impl AsRef<str>) -> Result<Self, SerializationError> {
+        let end = deserialize_open(exprs, source_text.as_ref(), false, true, "a range such as ..=5")
+            .contextualize("Error while deserializing a RangeToInclusive")?;
+        Ok(..=end)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::parser::Parser;
+    use crate::serialization::tests::{parse, round_trip};
+    use super::*;
+
+    #[test]
+    fn written_as_one_token() {
+        assert_eq!(parse::<Range<u16>>("8000..8100").unwrap(), 8000..8100);
+        assert_eq!(parse::<RangeInclusive<i32>>("-5..=5").unwrap(), -5..=5);
+        assert_eq!(parse::<RangeFrom<u16>>("8000..").unwrap(), 8000..);
+        assert_eq!(parse::<RangeTo<f64>>("..0.5").unwrap(), ..0.5);
+        assert_eq!(parse::<RangeToInclusive<i8>>("..=-1").unwrap(), ..=-1);
+    }
+
+    #[test]
+    fn written_as_a_list() {
+        assert_eq!(parse::<Range<u16>>("8000 8100").unwrap(), 8000..8100);
+        assert_eq!(parse::<RangeInclusive<u8>>("1 5").unwrap(), 1..=5);
+    }
+
+    #[test]
+    fn round_trips() {
+        let written = |expr: Expression| expr.dump();
+        assert_eq!(written((8000_u16..8100).serialize()), "8000..8100");
+        assert_eq!(written((1.5..=2.5).serialize()), "1.5..=2.5");
+        assert_eq!(written((3_u8..).serialize()), "3..");
+        assert_eq!(written((..=-3_i8).serialize()), "..=-3");
+        assert_eq!(written((String::from("a b")..String::from("c")).serialize()), "(\"a b\" c)");
+        assert_eq!(parse::<RangeInclusive<i64>>(&(-9_i64..=-2).serialize().dump()).unwrap(), -9..=-2);
+    }
+
+    #[test]
+    fn ends_which_would_read_back_differently() {
+        let infinite = 0.0..=f64::INFINITY;
+        assert_eq!(infinite.serialize().dump(), "(0.0 inf)");
+        assert_eq!(round_trip(&infinite), infinite);
+
+        let dotted = String::from("a.")..String::from("b");
+        assert_eq!(dotted.serialize().dump(), "(a. b)");
+        assert_eq!(round_trip(&dotted), dotted);
+    }
+
+    #[test]
+    fn open_ends_which_can_not_be_one_token() {
+        let from = String::from("a b")..;
+        assert_eq!(from.serialize().dump(), "(\"a b\" ..)");
+        assert_eq!(round_trip(&from), from);
+
+        let to = ..=f64::INFINITY;
+        assert_eq!(to.serialize().dump(), "(\"..=\" inf)");
+        assert_eq!(round_trip(&to), to);
+
+        let to = ..String::from("a.");
+        assert_eq!(round_trip(&to), to);
+    }
+
+    #[test]
+    fn only_a_lone_token_is_a_range() {
+        assert_eq!(parse::<Range<String>>("a..b c").unwrap(), String::from("a..b")..String::from("c"));
+        assert_eq!(parse::<Range<String>>("a..b c..d").unwrap(), String::from("a..b")..String::from("c..d"));
+    }
+
+    #[test]
+    fn inverted() {
+        let error = parse::<Range<u16>>("\n8100..8000").unwrap_err();
+        assert!(matches!(error.kind(), Kind::InvertedRange { start, end } if start == "8100" && end == "8000"));
+        assert!(error.to_string().contains("Error at 2:1"), "{}", error);
+        assert!(matches!(parse::<RangeInclusive<u8>>("5 1").unwrap_err().kind(), Kind::InvertedRange { .. }));
+        parse::<Range<u8>>("5..5").unwrap();
+    }
+
+    #[test]
+    fn bound_to_a_name() {
+        let source = "ports = 1..=5";
+        let mut exprs = Parser::new(source).parse().unwrap().into_iter();
+        let binding = exprs.find_binding("ports", source).unwrap();
+        assert_eq!(RangeInclusive::<u8>::deserialize(&mut binding.value.into_iter(), source).unwrap(), 1..=5);
+    }
+
+    #[test]
+    fn wrong_form() {
+        for error in [
+            parse::<Range<u8>>("1..=5").unwrap_err(),
+            parse::<RangeInclusive<u8>>("1..").unwrap_err(),
+            parse::<RangeFrom<u8>>("..5").unwrap_err(),
+            parse::<RangeTo<u8>>("5").unwrap_err(),
+        ] {
+            assert!(matches!(error.kind(), Kind::InvalidValue { .. }), "{:?}", error.kind());
+        }
+    }
+
+    #[test]
+    fn errors_point_at_the_end() {
+        let error = parse::<Range<u8>>("1..300").unwrap_err();
+        assert!(matches!(error.kind(), Kind::IntegerOutOfRange { .. }));
+        assert!(error.to_string().contains("Error at 1:4"), "{}", error);
+    }
+}
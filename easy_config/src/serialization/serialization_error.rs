@@ -22,13 +22,25 @@ pub enum Kind {
     ParseBoolError(ParseBoolError),
 
     ExpectedNumber(String),
+    ExpectedInteger(String),
+    ExpectedBool(String),
+    IntegerOutOfRange { literal: String, target: &'static str, range: String },
     ExpectedText(String),
+    InvalidValue { value: String, expected: &'static str },
+    InvertedRange { start: String, end: String },
+    Malformed { value: String, target: &'static str, reason: String },
     ExpectedPresence(Expression),
     ExpectedBinding(Expression),
     ExpectedList(Expression),
 
     ExpectedDiscriminant(String, &'static [&'static str]),
     MissingField(String),
+    DuplicateKey(String),
+    DuplicateElement(String),
+    InvalidMapKey { key: String, target: &'static str },
+    WrongValueType { expected: &'static str, got: &'static str },
+    Unrepresentable { value: String, format: &'static str },
+    Custom(String),
 
     ExpectedFieldGotEoi(String),
     ReachedEoi
@@ -79,12 +91,24 @@ impl Describe for Kind {
             Kind::ParseFloatError(e) => e.to_string(),
             Kind::ParseBoolError(e) => e.to_string(),
             Kind::ExpectedNumber(s) => format!("Expected number, but got {}.", s),
+            Kind::ExpectedInteger(s) => format!("Expected integer, but got {}.", s),
+            Kind::ExpectedBool(s) => format!("Expected true or false, but got {}.", s),
+            Kind::IntegerOutOfRange { literal, target, range } => format!("{} does not fit in a {}, which holds {}.", literal, target, range),
             Kind::ExpectedText(s) => format!("Expected text, but got {}.", s),
+            Kind::InvalidValue { value, expected } => format!("Expected {}, but got {}.", expected, value),
+            Kind::Malformed { value, target, reason } => format!("{} is not a valid {}: {}.", value, target, reason),
+            Kind::InvertedRange { start, end } => format!("The range starts at {}, which is after its end at {}.", start, end),
             Kind::ExpectedPresence(g) => format!("Expected Presence, but got {}.", g.data.name_of_kind()),
             Kind::ExpectedBinding(g) => format!("Expected Binding, but got {}.", g.data.name_of_kind()),
             Kind::ExpectedList(g) => format!("Expected List, but got {}.", g.data.name_of_kind()),
             Kind::ExpectedDiscriminant(got, options) => format!("Expected a enum discriminant (specifically {}), but got {}.", expected_from_options_text(options), got),
             Kind::MissingField(f) => format!("Expected to find the field: {}.", f),
+            Kind::DuplicateKey(k) => format!("The key {} appears more than once.", k),
+            Kind::DuplicateElement(e) => format!("The element {} appears more than once.", e),
+            Kind::InvalidMapKey { key, target } => format!("The key {} is not a valid {}.", key, target),
+            Kind::WrongValueType { expected, got } => format!("Expected a {} value, but got a {}.", expected, got),
+            Kind::Unrepresentable { value, format } => format!("{} can not be represented in {}.", value, format),
+            Kind::Custom(message) => message.clone(),
             Kind::ExpectedFieldGotEoi(e) => format!("Expected to find a field called {} but got to the end of the input.", e),
             Kind::ReachedEoi => "Reached the end of the file unexpectedly.".to_string()
         }
@@ -0,0 +1,252 @@
+//! Values written with a unit suffix, such as `timeout = 1m30s` or `cache = 512MiB`.
+
+use std::fmt::Display;
+use std::time::Duration;
+use crate::config_error::Contextualize;
+use crate::expression::{Atom, Expression, ExpressionData};
+use crate::expression_iterator::ExpressionIterator;
+use crate::serialization::EasyConfig;
+use crate::serialization::serialization_error::{Kind, SerializationError};
+
+const NANOS_PER_SECOND: u128 = 1_000_000_000;
+
+// Largest first, so a duration can be broken down greedily.
+const DURATION_UNITS: [(&str, u128); 7] = [
+    ("d", 86_400 * NANOS_PER_SECOND),
+    ("h", 3_600 * NANOS_PER_SECOND),
+    ("m", 60 * NANOS_PER_SECOND),
+    ("s", NANOS_PER_SECOND),
+    ("ms", 1_000_000),
+    ("us", 1_000),
+    ("ns", 1),
+];
+
+const BYTE_UNITS: [(&str, u64); 13] = [
+    ("B", 1),
+    ("kB", 1_000),
+    ("MB", 1_000_000),
+    ("GB", 1_000_000_000),
+    ("TB", 1_000_000_000_000),
+    ("PB", 1_000_000_000_000_000),
+    ("EB", 1_000_000_000_000_000_000),
+    ("KiB", 1 << 10),
+    ("MiB", 1 << 20),
+    ("GiB", 1 << 30),
+    ("TiB", 1 << 40),
+    ("PiB", 1 << 50),
+    ("EiB", 1 << 60),
+];
+
+// Split `text` into `(amount, unit)` pairs, such as `1m30s` into `[(1, "m"), (30, "s")]`.
+// Underscores may be used to group digits.
+fn quantities(text: &str) -> Option<Vec<(u128, &str)>> {
+    let mut quantities = Vec::new();
+    let mut rest = text;
+    while !rest.is_empty() {
+        let digits = rest.find(|c: char| !c.is_ascii_digit() && c != '_').unwrap_or(rest.len());
+        let unit = rest[digits..].find(|c: char| c.is_ascii_digit()).map_or(rest.len(), |x| x + digits);
+        let amount = rest[..digits].replace('_', "");
+        if amount.is_empty() {
+            return None
+        }
+        quantities.push((amount.parse().ok()?, &rest[digits..unit]));
+        rest = &rest[unit..];
+    }
+    Some(quantities)
+}
+
+fn parse_duration(text: &str) -> Option<Duration> {
+    let quantities = quantities(text)?;
+    if quantities.is_empty() {
+        return None
+    }
+
+    let mut nanos: u128 = 0;
+    for (amount, unit) in quantities {
+        let unit = match unit {
+            "µs" => 1_000,
+            // A bare zero needs no unit.
+            "" if amount == 0 => 0,
+            unit => DURATION_UNITS.iter().find(|(name, _)| *name == unit)?.1,
+        };
+        nanos = nanos.checked_add(amount.checked_mul(unit)?)?;
+    }
+
+    let seconds = u64::try_from(nanos / NANOS_PER_SECOND).ok()?;
+    Some(Duration::new(seconds, (nanos % NANOS_PER_SECOND) as u32))
+}
+
+// Written with whichever run of units breaks the duration down exactly and gives the shortest
+// text, like `ByteSize`. So 90 seconds is `90s`, but an hour and a second is `1h1s`.
+fn write_duration(duration: &Duration) -> String {
+    let nanos = duration.as_nanos();
+    if nanos == 0 {
+        return "0s".to_string()
+    }
+
+    let breakdown = |units: &[(&str, u128)]| {
+        let mut rest = nanos;
+        let mut written = String::new();
+        for (name, unit) in units {
+            if rest >= *unit {
+                written.push_str(&format!("{}{}", rest / unit, name));
+                rest %= unit;
+            }
+        }
+        (rest == 0).then_some(written)
+    };
+
+    (0..DURATION_UNITS.len())
+        .flat_map(|largest| (largest..DURATION_UNITS.len()).map(move |smallest| &DURATION_UNITS[largest..=smallest]))
+        .filter_map(breakdown)
+        .min_by_key(|x| x.len())
+        .expect("every duration is a whole number of nanoseconds")
+}
+
+// Durations and sizes are single atoms, but `30s` is a number while `s30` would be text.
+fn next_text(exprs: &mut ExpressionIterator, source_text: &str) -> Result<(String, Expression), SerializationError> {
+    let expr = exprs.minimized_next_or_err(source_text)?;
+    match &expr.data {
+        ExpressionData::Presence(atom, _) => Ok((atom.to_text(), expr)),
+        _ => Err(SerializationError::on_span(Kind::ExpectedPresence(expr.clone()), expr.span(), source_text)),
+    }
+}
+
+impl EasyConfig for Duration {
+    fn serialize(&self) -> Expression {
+        Expression::presence(Atom::classify(write_duration(self)))
+    }
+
+    fn deserialize(exprs: &mut ExpressionIterator, source_text: impl AsRef<str>) -> Result<Self, SerializationError> {
+        let source_text = source_text.as_ref();
+        let (text, expr) = next_text(exprs, source_text)?;
+        parse_duration(&text)
+            .ok_or_else(|| SerializationError::on_span(
+                Kind::InvalidValue { value: text, expected: "a duration such as 30s, 1m30s or 250ms" },
+                expr.span(),
+                source_text
+            ))
+            .contextualize("Error while deserializing a Duration")
+    }
+}
+
+/// A number of bytes, written with a decimal (`kB`, `MB`, ...) or binary (`KiB`, `MiB`, ...)
+/// unit such as `512MiB`. A number without a unit is a count of bytes.
+#[derive(Debug, Copy, Clone, Default, Ord, PartialOrd, Eq, PartialEq, Hash)]
+pub struct ByteSize(pub u64);
+
+impl ByteSize {
+    pub fn bytes(&self) -> u64 {
+        self.0
+    }
+
+    fn parse(text: &str) -> Option<Self> {
+        let [(amount, unit)] = quantities(text)?[..] else {
+            return None
+        };
+
+        let unit = match unit {
+            "" | "b" => 1,
+            "KB" => 1_000,
+            unit => BYTE_UNITS.iter().find(|(name, _)| *name == unit)?.1,
+        };
+        u64::try_from(amount).ok()?.checked_mul(unit).map(ByteSize)
+    }
+}
+
+impl From<u64> for ByteSize {
+    fn from(bytes: u64) -> Self {
+        ByteSize(bytes)
+    }
+}
+
+// Written with whichever unit divides the size exactly and gives the shortest text.
+impl Display for ByteSize {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let written = BYTE_UNITS.iter()
+            .filter(|(_, unit)| self.0.is_multiple_of(*unit))
+            .map(|(name, unit)| format!("{}{}", self.0 / unit, name))
+            .min_by_key(|x| x.len())
+            .expect("every size is a whole number of bytes");
+        write!(f, "{}", written)
+    }
+}
+
+impl EasyConfig for ByteSize {
+    fn serialize(&self) -> Expression {
+        Expression::presence(Atom::classify(self.to_string()))
+    }
+
+    fn deserialize(exprs: &mut ExpressionIterator, source_text: impl AsRef<str>) -> Result<Self, SerializationError> {
+        let source_text = source_text.as_ref();
+        let (text, expr) = next_text(exprs, source_text)?;
+        ByteSize::parse(&text)
+            .ok_or_else(|| SerializationError::on_span(
+                Kind::InvalidValue { value: text, expected: "a size such as 4096, 64kB or 512MiB" },
+                expr.span(),
+                source_text
+            ))
+            .contextualize("Error while deserializing a ByteSize")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::serialization::tests::parse;
+    use super::*;
+
+    #[test]
+    fn durations() {
+        assert_eq!(parse::<Duration>("30s").unwrap(), Duration::from_secs(30));
+        assert_eq!(parse::<Duration>("1m30s").unwrap(), Duration::from_secs(90));
+        assert_eq!(parse::<Duration>("1h2m3s4ms").unwrap(), Duration::new(3723, 4_000_000));
+        assert_eq!(parse::<Duration>("250µs").unwrap(), Duration::from_micros(250));
+        assert_eq!(parse::<Duration>("0").unwrap(), Duration::ZERO);
+    }
+
+    #[test]
+    fn written_durations() {
+        let written = |duration: Duration| duration.serialize().dump();
+        assert_eq!(written(Duration::from_secs(90)), "90s");
+        assert_eq!(written(Duration::from_secs(5400)), "90m");
+        assert_eq!(written(Duration::from_secs(86_400)), "1d");
+        assert_eq!(written(Duration::from_millis(1500)), "1500ms");
+        assert_eq!(written(Duration::from_secs(3601)), "1h1s");
+        assert_eq!(written(Duration::new(3723, 4_000_000)), "62m3s4ms");
+        assert_eq!(written(Duration::ZERO), "0s");
+    }
+
+    #[test]
+    fn duration_round_trip() {
+        for duration in [Duration::new(3723, 4_000_000), Duration::from_nanos(1), Duration::MAX] {
+            assert_eq!(parse::<Duration>(&duration.serialize().dump()).unwrap(), duration);
+        }
+    }
+
+    #[test]
+    fn invalid_durations() {
+        for source in ["30", "1x", "s", "1.5s", "true", "18446744073709551616s"] {
+            let error = parse::<Duration>(source).unwrap_err();
+            assert!(matches!(error.kind(), Kind::InvalidValue { .. }), "{}: {:?}", source, error.kind());
+        }
+    }
+
+    #[test]
+    fn byte_sizes() {
+        assert_eq!(parse::<ByteSize>("512MiB").unwrap(), ByteSize(512 << 20));
+        assert_eq!(parse::<ByteSize>("64kB").unwrap(), ByteSize(64_000));
+        assert_eq!(parse::<ByteSize>("4096").unwrap(), ByteSize(4096));
+        assert_eq!(parse::<ByteSize>("1_000B").unwrap(), ByteSize(1000));
+        assert!(matches!(parse::<ByteSize>("1MiB1KiB").unwrap_err().kind(), Kind::InvalidValue { .. }));
+        assert!(matches!(parse::<ByteSize>("16EiB").unwrap_err().kind(), Kind::InvalidValue { .. }));
+    }
+
+    #[test]
+    fn shortest_byte_size() {
+        assert_eq!(ByteSize(512 << 20).to_string(), "512MiB");
+        assert_eq!(ByteSize(1000).to_string(), "1kB");
+        assert_eq!(ByteSize(1500).to_string(), "1500B");
+        assert_eq!(ByteSize(0).to_string(), "0B");
+        assert_eq!(parse::<ByteSize>(&ByteSize(3 << 30).serialize().dump()).unwrap(), ByteSize(3 << 30));
+    }
+}
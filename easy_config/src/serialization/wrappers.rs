@@ -0,0 +1,188 @@
+use std::any;
+use std::borrow::Cow;
+use std::cell::{Cell, RefCell};
+use std::marker::PhantomData;
+use std::num::{NonZeroI128, NonZeroI16, NonZeroI32, NonZeroI64, NonZeroI8, NonZeroIsize, NonZeroU128, NonZeroU16, NonZeroU32, NonZeroU64, NonZeroU8, NonZeroUsize, Wrapping};
+use std::rc::Rc;
+use std::sync::{Arc, Mutex, PoisonError, RwLock};
+use crate::config_error::Contextualize;
+use crate::expression::{Expression, ExpressionData};
+use crate::expression_iterator::ExpressionIterator;
+use crate::lexical_span::LexicalSpan;
+use crate::serialization::EasyConfig;
+use crate::serialization::serialization_error::{Kind, SerializationError};
+
+// Wrappers are written exactly as the value they hold, so they forward PASSTHROUGH too.
+macro_rules! config_wrapper {
+    ($wrapper: ident, |$value: ident| $serialize: expr) => {
+        impl<T: EasyConfig> EasyConfig for $wrapper<T> {
+            const PASSTHROUGH: bool = T::PASSTHROUGH;
+
+            fn serialize(&self) -> Expression {
+                let $value = self;
+                $serialize
+            }
+
+            fn deserialize(exprs: &mut ExpressionIterator, source_text: impl AsRef<str>) -> Result<Self, SerializationError> {
+                T::deserialize(exprs, source_text).map($wrapper::new)
+            }
+        }
+    };
+}
+
+config_wrapper!(Box, |x| (**x).serialize());
+config_wrapper!(Rc, |x| (**x).serialize());
+config_wrapper!(Arc, |x| (**x).serialize());
+config_wrapper!(RefCell, |x| x.borrow().serialize());
+// A poisoned lock still holds a value worth writing out.
+config_wrapper!(Mutex, |x| x.lock().unwrap_or_else(PoisonError::into_inner).serialize());
+config_wrapper!(RwLock, |x| x.read().unwrap_or_else(PoisonError::into_inner).serialize());
+
+impl<T: EasyConfig + Copy> EasyConfig for Cell<T> {
+    const PASSTHROUGH: bool = T::PASSTHROUGH;
+
+    fn serialize(&self) -> Expression {
+        self.get().serialize()
+    }
+
+    fn deserialize(exprs: &mut ExpressionIterator, source_text: impl AsRef<str>) -> Result<Self, SerializationError> {
+        T::deserialize(exprs, source_text).map(Cell::new)
+    }
+}
+
+impl<T: EasyConfig> EasyConfig for Wrapping<T> {
+    const PASSTHROUGH: bool = T::PASSTHROUGH;
+
+    fn serialize(&self) -> Expression {
+        self.0.serialize()
+    }
+
+    fn deserialize(exprs: &mut ExpressionIterator, source_text: impl AsRef<str>) -> Result<Self, SerializationError> {
+        T::deserialize(exprs, source_text).map(Wrapping)
+    }
+}
+
+impl EasyConfig for Cow<'static, str> {
+    fn serialize(&self) -> Expression {
+        self.to_string().serialize()
+    }
+
+    fn deserialize(exprs: &mut ExpressionIterator, source_text: impl AsRef<str>) -> Result<Self, SerializationError> {
+        String::deserialize(exprs, source_text).map(Cow::Owned)
+    }
+}
+
+macro_rules! config_non_zero {
+    ($ty: ty, $inner: ty) => {
+        impl EasyConfig for $ty {
+            fn serialize(&self) -> Expression {
+                self.get().serialize()
+            }
+
+            fn deserialize(exprs: &mut ExpressionIterator, source_text: impl AsRef<str>) -> Result<Self, SerializationError> {
+                let source_text = source_text.as_ref();
+                let span = exprs.peek().map(|x| x.span()).unwrap_or(LexicalSpan::zeros());
+                let value = <$inner>::deserialize(exprs, source_text)?;
+                <$ty>::new(value)
+                    .ok_or_else(|| SerializationError::on_span(
+                        Kind::InvalidValue { value: value.to_string(), expected: "a non-zero integer" },
+                        span,
+                        source_text
+                    ))
+                    .contextualize(format!("Error while deserializing a {}", stringify!($ty)))
+            }
+        }
+    };
+}
+
+config_non_zero!(NonZeroI8, i8);
+config_non_zero!(NonZeroI16, i16);
+config_non_zero!(NonZeroI32, i32);
+config_non_zero!(NonZeroI64, i64);
+config_non_zero!(NonZeroI128, i128);
+config_non_zero!(NonZeroIsize, isize);
+
+config_non_zero!(NonZeroU8, u8);
+config_non_zero!(NonZeroU16, u16);
+config_non_zero!(NonZeroU32, u32);
+config_non_zero!(NonZeroU64, u64);
+config_non_zero!(NonZeroU128, u128);
+config_non_zero!(NonZeroUsize, usize);
+
+// Nothing is stored, so this is written as an empty list.
+impl EasyConfig for () {
+    fn serialize(&self) -> Expression {
+        Expression::list(vec![])
+    }
+
+    fn deserialize(exprs: &mut ExpressionIterator, source_text: impl AsRef<str>) -> Result<Self, SerializationError> {
+        let Some(expr) = exprs.next() else {
+            return Ok(())
+        };
+
+        if matches!(&expr.data, ExpressionData::List(elements, _) if elements.is_empty()) {
+            return Ok(())
+        }
+
+        Err(SerializationError::on_span(
+            Kind::WrongCardinality { got: 1 + exprs.count(), want: 0 },
+            expr.span(),
+            source_text
+        )).contextualize("Error while deserializing ()")
+    }
+}
+
+impl<T: ?Sized + 'static> EasyConfig for PhantomData<T> {
+    fn serialize(&self) -> Expression {
+        ().serialize()
+    }
+
+    fn deserialize(exprs: &mut ExpressionIterator, source_text: impl AsRef<str>) -> Result<Self, SerializationError> {
+        <()>::deserialize(exprs, source_text)
+            .map(|_| PhantomData)
+            .contextualize(format!("Error while deserializing PhantomData<{}>", any::type_name::<T>()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::serialization::tests::parse;
+    use super::*;
+
+    #[test]
+    fn pointers() {
+        assert_eq!(*parse::<Box<u8>>("3").unwrap(), 3);
+        assert_eq!(*parse::<Rc<Vec<u8>>>("1 2").unwrap(), vec![1, 2]);
+        assert_eq!(Arc::new(Some(4_u8)).serialize().dump(), Some(4_u8).serialize().dump());
+    }
+
+    #[test]
+    fn cells_and_locks() {
+        assert_eq!(parse::<Cell<u8>>("5").unwrap().get(), 5);
+        assert!(*parse::<RefCell<bool>>("true").unwrap().borrow());
+        assert_eq!(Mutex::new(7_u8).serialize().dump(), "7");
+        assert_eq!(*parse::<RwLock<i8>>("-1").unwrap().read().unwrap(), -1);
+    }
+
+    #[test]
+    fn wrapping_and_cow() {
+        assert_eq!(parse::<Wrapping<u8>>("255").unwrap(), Wrapping(255));
+        assert_eq!(parse::<Cow<'static, str>>("hi there").unwrap(), "hi there");
+    }
+
+    #[test]
+    fn non_zero() {
+        assert_eq!(parse::<NonZeroU16>("8080").unwrap().get(), 8080);
+        let error = parse::<NonZeroU32>("0").unwrap_err();
+        assert!(matches!(error.kind(), Kind::InvalidValue { value, .. } if value == "0"));
+        assert!(matches!(parse::<NonZeroU8>("256").unwrap_err().kind(), Kind::IntegerOutOfRange { .. }));
+    }
+
+    #[test]
+    fn unit() {
+        assert_eq!(().serialize().dump(), "()");
+        parse::<()>("()").unwrap();
+        parse::<PhantomData<String>>("").unwrap();
+        assert!(matches!(parse::<()>("a b").unwrap_err().kind(), Kind::WrongCardinality { got: 2, want: 0 }));
+    }
+}
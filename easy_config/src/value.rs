@@ -0,0 +1,484 @@
+use std::collections::BTreeMap;
+use std::fmt::Display;
+use std::ops::{Index, IndexMut};
+use crate::atom::{Atom, Integer};
+use crate::config_error::Contextualize;
+use crate::expression::{Expression, ExpressionData};
+use crate::expression_iterator::ExpressionIterator;
+use crate::serialization::EasyConfig;
+use crate::serialization::serialization_error::{Kind, SerializationError};
+
+/// Any configuration, for when its shape is only known at runtime.
+///
+/// A list containing at least one binding is read as a `Map`. Bare words next to those bindings
+/// become `Flag`s, so `(verbose port = 80)` maps `verbose` to `Flag` and `port` to `Integer`. Any
+/// other list is read as a `List`, and presences are read according to the type of their atom.
+///
+/// Like `Vec` and `String` fields, a value containing a single element is written as that
+/// element alone, so a one element `List` reads back as the element itself. Maps are always
+/// written in parentheses, but one holding nothing except flags reads back as a `List` of words.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Map(BTreeMap<String, Value>),
+    List(Vec<Value>),
+    String(String),
+    Integer(Integer),
+    Float(f64),
+    Bool(bool),
+    /// A key which is present in a map without a value. Outside of a map it is written as `true`.
+    Flag,
+}
+
+impl Value {
+    pub fn kind_name(&self) -> &'static str {
+        match self {
+            Value::Map(_) => "Map",
+            Value::List(_) => "List",
+            Value::String(_) => "String",
+            Value::Integer(_) => "Integer",
+            Value::Float(_) => "Float",
+            Value::Bool(_) => "Bool",
+            Value::Flag => "Flag",
+        }
+    }
+
+    pub fn is_map(&self) -> bool {
+        matches!(self, Value::Map(_))
+    }
+    pub fn is_list(&self) -> bool {
+        matches!(self, Value::List(_))
+    }
+    pub fn is_string(&self) -> bool {
+        matches!(self, Value::String(_))
+    }
+    pub fn is_integer(&self) -> bool {
+        matches!(self, Value::Integer(_))
+    }
+    pub fn is_float(&self) -> bool {
+        matches!(self, Value::Float(_))
+    }
+    pub fn is_bool(&self) -> bool {
+        matches!(self, Value::Bool(_))
+    }
+    pub fn is_flag(&self) -> bool {
+        matches!(self, Value::Flag)
+    }
+
+    pub fn as_map(&self) -> Option<&BTreeMap<String, Value>> {
+        match self {
+            Value::Map(m) => Some(m),
+            _ => None,
+        }
+    }
+    pub fn as_map_mut(&mut self) -> Option<&mut BTreeMap<String, Value>> {
+        match self {
+            Value::Map(m) => Some(m),
+            _ => None,
+        }
+    }
+    pub fn as_list(&self) -> Option<&Vec<Value>> {
+        match self {
+            Value::List(l) => Some(l),
+            _ => None,
+        }
+    }
+    pub fn as_list_mut(&mut self) -> Option<&mut Vec<Value>> {
+        match self {
+            Value::List(l) => Some(l),
+            _ => None,
+        }
+    }
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Value::String(s) => Some(s),
+            _ => None,
+        }
+    }
+    pub fn as_integer(&self) -> Option<Integer> {
+        match self {
+            Value::Integer(i) => Some(*i),
+            _ => None,
+        }
+    }
+    pub fn as_i64(&self) -> Option<i64> {
+        self.as_integer().and_then(|x| x.convert())
+    }
+    pub fn as_u64(&self) -> Option<u64> {
+        self.as_integer().and_then(|x| x.convert())
+    }
+    /// Integers are widened to floats, the same way the `f64` deserializer does.
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            Value::Float(f) => Some(*f),
+            Value::Integer(i) => Some(i.to_f64()),
+            _ => None,
+        }
+    }
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            Value::Bool(b) => Some(*b),
+            _ => None,
+        }
+    }
+
+    /// Look up `key` if this is a map.
+    pub fn get(&self, key: impl AsRef<str>) -> Option<&Value> {
+        self.as_map()?.get(key.as_ref())
+    }
+    pub fn get_mut(&mut self, key: impl AsRef<str>) -> Option<&mut Value> {
+        self.as_map_mut()?.get_mut(key.as_ref())
+    }
+
+    // The elements of a list, or of the iterator handed to `deserialize`, which has already had
+    // its surrounding list removed.
+    fn from_elements(elements: Vec<Expression>, source_text: &str) -> Result<Value, SerializationError> {
+        if elements.iter().any(|x| x.is_binding()) {
+            return Self::map_from_elements(elements, source_text)
+        }
+
+        Ok(Value::List(Result::from_iter(
+            elements.into_iter().map(|x| Self::from_expression(x, source_text))
+        )?))
+    }
+
+    fn map_from_elements(elements: Vec<Expression>, source_text: &str) -> Result<Value, SerializationError> {
+        let mut map = BTreeMap::new();
+        for element in elements {
+            let span = element.span();
+            let (key, value) = match element.data {
+                ExpressionData::BindingExpr(binding) => {
+                    let value = Self::from_expression(*binding.value, source_text)
+                        .contextualize(format!("Error while deserializing the value of {}", binding.name))?;
+                    (binding.name, value)
+                }
+                ExpressionData::Presence(Atom::Text(flag) | Atom::Quoted(flag), _) => (flag, Value::Flag),
+                _ => return Err(SerializationError::on_span(Kind::ExpectedBinding(element), span, source_text))
+                    .contextualize("Only bindings and flags may appear in a map"),
+            };
+
+            if map.contains_key(&key) {
+                return Err(SerializationError::on_span(Kind::DuplicateKey(key), span, source_text))
+            }
+            map.insert(key, value);
+        }
+
+        Ok(Value::Map(map))
+    }
+
+    fn from_expression(expression: Expression, source_text: &str) -> Result<Value, SerializationError> {
+        match expression.data {
+            ExpressionData::Presence(atom, _) => Ok(Value::from(atom)),
+            ExpressionData::BindingExpr(_) => Self::map_from_elements(vec![expression], source_text),
+            ExpressionData::List(elements, _) => Self::from_elements(elements, source_text),
+        }
+    }
+}
+
+impl From<Atom> for Value {
+    fn from(atom: Atom) -> Self {
+        match atom {
+            Atom::Text(s) | Atom::Number(s) | Atom::Quoted(s) => Value::String(s),
+            Atom::Integer(i) => Value::Integer(i),
+            Atom::Float(f) => Value::Float(f.value()),
+            Atom::Bool(b) => Value::Bool(b),
+        }
+    }
+}
+
+impl EasyConfig for Value {
+    fn serialize(&self) -> Expression {
+        match self {
+            Value::Map(map) => Expression::list(
+                map.iter()
+                    .map(|(k, v)| match v {
+                        Value::Flag => Expression::presence(Atom::string(k.clone())),
+                        v => Expression::binding(k, v.serialize()),
+                    })
+                    .collect()
+            ),
+            Value::List(list) => Expression::list(list.iter().map(|x| x.serialize()).collect()).minimized(),
+            Value::String(s) => Expression::presence(Atom::string(s.clone())),
+            Value::Integer(i) => Expression::presence(*i),
+            Value::Float(f) => Expression::presence(*f),
+            Value::Bool(b) => Expression::presence(*b),
+            Value::Flag => Expression::presence(true),
+        }
+    }
+
+    fn deserialize(exprs: &mut ExpressionIterator, source_text: impl AsRef<str>) -> Result<Self, SerializationError>
+    where
+        Self: Sized
+    {
+        let source_text = source_text.as_ref();
+        let mut elements: Vec<Expression> = exprs.collect();
+
+        let value = if elements.len() == 1 && !elements[0].is_binding() {
+            Value::from_expression(elements.remove(0), source_text)
+        } else {
+            Value::from_elements(elements, source_text)
+        };
+
+        value.contextualize("Error while deserializing a Value")
+    }
+}
+
+impl Display for Value {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.serialize().dump())
+    }
+}
+
+impl Index<&str> for Value {
+    type Output = Value;
+
+    /// Panics if this is not a map, or the key is missing.
+    fn index(&self, key: &str) -> &Value {
+        match self {
+            Value::Map(map) => map.get(key).unwrap_or_else(|| panic!("The key {} is not in this map.", key)),
+            other => panic!("Attempted to index a {} by the key {}.", other.kind_name(), key),
+        }
+    }
+}
+impl IndexMut<&str> for Value {
+    fn index_mut(&mut self, key: &str) -> &mut Value {
+        match self {
+            Value::Map(map) => map.get_mut(key).unwrap_or_else(|| panic!("The key {} is not in this map.", key)),
+            other => panic!("Attempted to index a {} by the key {}.", other.kind_name(), key),
+        }
+    }
+}
+impl Index<usize> for Value {
+    type Output = Value;
+
+    /// Panics if this is not a list, or the index is out of bounds.
+    fn index(&self, index: usize) -> &Value {
+        match self {
+            Value::List(list) => &list[index],
+            other => panic!("Attempted to index a {} by the position {}.", other.kind_name(), index),
+        }
+    }
+}
+impl IndexMut<usize> for Value {
+    fn index_mut(&mut self, index: usize) -> &mut Value {
+        match self {
+            Value::List(list) => &mut list[index],
+            other => panic!("Attempted to index a {} by the position {}.", other.kind_name(), index),
+        }
+    }
+}
+
+fn wrong_type(expected: &'static str, got: &Value) -> SerializationError {
+    SerializationError::FirstLevelError(Kind::WrongValueType { expected, got: got.kind_name() }, String::new())
+}
+
+macro_rules! value_conversions {
+    ($ty:ty, $variant:ident, $expected:literal, $from:expr, $to:expr) => {
+        impl From<$ty> for Value {
+            fn from(value: $ty) -> Value {
+                Value::$variant($from(value))
+            }
+        }
+
+        impl TryFrom<Value> for $ty {
+            type Error = SerializationError;
+
+            fn try_from(value: Value) -> Result<Self, Self::Error> {
+                $to(&value).ok_or_else(|| wrong_type($expected, &value))
+            }
+        }
+    };
+}
+
+macro_rules! integer_conversions {
+    ($ty:ty) => {
+        value_conversions!($ty, Integer, "Integer", Integer::from, |x: &Value| x.as_integer().and_then(|i| i.convert()));
+    };
+}
+
+integer_conversions!(i8);
+integer_conversions!(i16);
+integer_conversions!(i32);
+integer_conversions!(i64);
+integer_conversions!(i128);
+integer_conversions!(isize);
+
+integer_conversions!(u8);
+integer_conversions!(u16);
+integer_conversions!(u32);
+integer_conversions!(u64);
+integer_conversions!(u128);
+integer_conversions!(usize);
+
+value_conversions!(f64, Float, "Float", |x| x, Value::as_f64);
+value_conversions!(f32, Float, "Float", f64::from, |x: &Value| x.as_f64().map(|f| f as f32));
+value_conversions!(bool, Bool, "Bool", |x| x, Value::as_bool);
+
+impl From<Integer> for Value {
+    fn from(value: Integer) -> Self {
+        Value::Integer(value)
+    }
+}
+impl From<&str> for Value {
+    fn from(value: &str) -> Self {
+        Value::String(value.to_string())
+    }
+}
+impl From<String> for Value {
+    fn from(value: String) -> Self {
+        Value::String(value)
+    }
+}
+impl TryFrom<Value> for String {
+    type Error = SerializationError;
+
+    fn try_from(value: Value) -> Result<Self, Self::Error> {
+        match value {
+            Value::String(s) => Ok(s),
+            other => Err(wrong_type("String", &other)),
+        }
+    }
+}
+impl From<Vec<Value>> for Value {
+    fn from(value: Vec<Value>) -> Self {
+        Value::List(value)
+    }
+}
+impl TryFrom<Value> for Vec<Value> {
+    type Error = SerializationError;
+
+    fn try_from(value: Value) -> Result<Self, Self::Error> {
+        match value {
+            Value::List(l) => Ok(l),
+            other => Err(wrong_type("List", &other)),
+        }
+    }
+}
+impl From<BTreeMap<String, Value>> for Value {
+    fn from(value: BTreeMap<String, Value>) -> Self {
+        Value::Map(value)
+    }
+}
+impl TryFrom<Value> for BTreeMap<String, Value> {
+    type Error = SerializationError;
+
+    fn try_from(value: Value) -> Result<Self, Self::Error> {
+        match value {
+            Value::Map(m) => Ok(m),
+            other => Err(wrong_type("Map", &other)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::serialization::tests::parse;
+    use super::*;
+
+    #[test]
+    fn scalars() {
+        assert_eq!(parse::<Value>("cat").unwrap(), Value::from("cat"));
+        assert_eq!(parse::<Value>("\"two words\"").unwrap(), Value::from("two words"));
+        assert_eq!(parse::<Value>("-12").unwrap(), Value::from(-12));
+        assert_eq!(parse::<Value>("1.5").unwrap(), Value::from(1.5));
+        assert_eq!(parse::<Value>("true").unwrap(), Value::from(true));
+        assert_eq!(parse::<Value>("1.2.3.4").unwrap(), Value::from("1.2.3.4"));
+    }
+
+    #[test]
+    fn map_with_flags() {
+        let value = parse::<Value>("verbose name = plugin port = 80 tags = (a b)").unwrap();
+        assert!(value["verbose"].is_flag());
+        assert_eq!(value["name"].as_str(), Some("plugin"));
+        assert_eq!(value["port"].as_u64(), Some(80));
+        assert_eq!(value["tags"][1], Value::from("b"));
+        assert_eq!(value.get("missing"), None);
+    }
+
+    #[test]
+    fn list() {
+        let value = parse::<Value>("1 two (3 4)").unwrap();
+        assert_eq!(value, Value::List(vec![
+            Value::from(1),
+            Value::from("two"),
+            Value::List(vec![Value::from(3), Value::from(4)]),
+        ]));
+    }
+
+    #[test]
+    fn nested_map() {
+        let value = parse::<Value>("plugin = (name = x options = (depth = 2))").unwrap();
+        assert_eq!(value["plugin"]["options"]["depth"].as_i64(), Some(2));
+    }
+
+    #[test]
+    fn duplicate_keys() {
+        let error = parse::<Value>("a = 1 a = 2").unwrap_err();
+        assert!(matches!(error.kind(), Kind::DuplicateKey(k) if k == "a"));
+    }
+
+    #[test]
+    fn lists_in_maps_are_rejected() {
+        let error = parse::<Value>("(b c) a = 1").unwrap_err();
+        assert!(matches!(error.kind(), Kind::ExpectedBinding(_)));
+    }
+
+    #[test]
+    fn round_trip() {
+        let source = "verbose name = \"my plugin\" port = 80 ratio = 0.5 tags = (a b) inner = (on = false)";
+        let value = parse::<Value>(source).unwrap();
+        let dumped = value.serialize().dump();
+        assert_eq!(parse::<Value>(&dumped).unwrap(), value);
+    }
+
+    #[test]
+    fn maps_keep_their_shape() {
+        let map = Value::Map([("a".to_string(), Value::from(1))].into());
+        let list = Value::List(vec![map.clone(), Value::from(2)]);
+        assert_eq!(parse::<Value>(&list.serialize().dump()).unwrap(), list);
+
+        let flags = Value::Map([("two words".to_string(), Value::Flag), ("12".to_string(), Value::Flag), ("b".to_string(), Value::from(true))].into());
+        assert_eq!(parse::<Value>(&flags.serialize().dump()).unwrap(), flags);
+    }
+
+    // Values whose text can be read back as something else, as described on `Value`, aren't
+    // generated: one element lists, maps without a binding, flags outside of a map, and NaN.
+    fn any_value() -> impl proptest::strategy::Strategy<Value = Value> {
+        use proptest::prelude::*;
+
+        let leaf = prop_oneof![
+            any::<String>().prop_map(Value::String),
+            any::<i64>().prop_map(Value::from),
+            any::<f64>().prop_filter("NaN never equals itself", |x| !x.is_nan()).prop_map(Value::Float),
+            any::<bool>().prop_map(Value::Bool),
+        ];
+        leaf.prop_recursive(4, 32, 4, |inner| prop_oneof![
+            prop_oneof![Just(Vec::new()), proptest::collection::vec(inner.clone(), 2..5)].prop_map(Value::List),
+            (
+                proptest::collection::btree_map(any::<String>(), prop_oneof![Just(Value::Flag), inner.clone()], 0..4),
+                any::<String>(),
+                inner,
+            ).prop_map(|(mut map, key, value)| {
+                map.insert(key, value);
+                Value::Map(map)
+            }),
+        ])
+    }
+
+    proptest::proptest! {
+        #[test]
+        fn any_value_round_trips(value in any_value()) {
+            let text = value.serialize().dump();
+            proptest::prop_assert_eq!(parse::<Value>(&text).unwrap(), value, "{}", text);
+        }
+    }
+
+    #[test]
+    fn conversions() {
+        assert_eq!(u8::try_from(Value::from(200)).unwrap(), 200);
+        assert!(u8::try_from(Value::from(300)).is_err());
+        assert_eq!(f64::try_from(Value::from(2)).unwrap(), 2.0);
+        assert_eq!(String::try_from(Value::from("x")).unwrap(), "x");
+        let error = bool::try_from(Value::from("x")).unwrap_err();
+        assert!(matches!(error.kind(), Kind::WrongValueType { expected: "Bool", got: "String" }));
+    }
+}
@@ -15,7 +15,7 @@ enum Complex {
 mod tests {
     use easy_config::serialization::EasyConfig;
     use easy_config::expression::Expression;
-    use easy_config::parser::Parser;
+    use crate::helpers::parse;
     use super::*;
 
     #[test]
@@ -51,21 +51,21 @@ mod tests {
     #[test]
     fn unit_deserialize() {
         let text = "(Unit)";
-        let result = Complex::deserialize(&mut Parser::new(text).parse().unwrap().into_iter(), text).unwrap();
+        let result = parse::<Complex>(text).unwrap();
         assert_eq!(result, Complex::Unit);
     }
 
     #[test]
     fn named_deserialize() {
         let text = "(Named (x=1 y=2))";
-        let result = Complex::deserialize(&mut Parser::new(text).parse().unwrap().into_iter(), text).unwrap();
+        let result = parse::<Complex>(text).unwrap();
         assert_eq!(result, Complex::Named { x: 1, y: 2 });
     }
 
     #[test]
     fn unnamed_deserialize() {
         let text = "(Unnamed (3 4))";
-        let result = Complex::deserialize(&mut Parser::new(text).parse().unwrap().into_iter(), text).unwrap();
+        let result = parse::<Complex>(text).unwrap();
         assert_eq!(result, Complex::Unnamed(3, 4));
     }
 }
\ No newline at end of file
@@ -0,0 +1,101 @@
+#[cfg(test)]
+mod tests {
+    use easy_config::ec;
+    use easy_config::expression::{Atom, Expression};
+    use easy_config::parser::Parser;
+
+    #[test]
+    fn presence() {
+        assert_eq!(ec!(cat), Expression::presence("cat"));
+    }
+
+    #[test]
+    fn numbers() {
+        assert_eq!(ec!(-1), Expression::presence(-1));
+        assert_eq!(ec!(0x1F), Expression::presence(Atom::classify("0x1F")));
+        assert_eq!(ec!(1.5), Expression::presence(1.5));
+        assert_eq!(ec!(127.0.0.1), Expression::presence(Atom::Number("127.0.0.1".to_string())));
+    }
+
+    #[test]
+    fn atoms_are_classified_like_the_parser() {
+        let built = [
+            (ec!(1.50), "1.50"),
+            (ec!(-0b101), "-0b101"),
+            (ec!(1e3), "1e3"),
+            (ec!(-inf), "-inf"),
+            (ec!(340282366920938463463374607431768211456), "340282366920938463463374607431768211456"),
+            (ec!(false), "false"),
+            (ec!(src/content), "src/content"),
+        ];
+        for (expr, text) in built {
+            assert_eq!(expr, Expression::presence(Atom::classify(text)));
+            assert_eq!(expr.dump(), text);
+        }
+    }
+
+    #[test]
+    fn binding_names_which_are_not_identifiers() {
+        assert_eq!(ec!(1 = a), Expression::binding("1", Expression::presence("a")));
+        assert_eq!(ec!(1 = a).dump(), "1 = a");
+
+        let expr = ec!(1.5 = a);
+        assert_eq!(expr.dump(), "\"1.5\" = a");
+        let parsed = Parser::new(expr.dump()).parse().unwrap().into_iter().next().unwrap();
+        assert!(parsed.semantic_eq(&expr));
+    }
+
+    #[test]
+    fn bools() {
+        assert_eq!(ec!(true), Expression::presence(true));
+    }
+
+    #[test]
+    fn binding() {
+        assert_eq!(ec!(name = cat), Expression::binding("name", Expression::presence("cat")));
+    }
+
+    #[test]
+    fn matches_parser() {
+        let text = "name = cat addresses = (None (IpV4 (127.0.0.1)) (Index (3 -1))) folders = (src/content src/static) bind = Some(thing)";
+        let parsed = Parser::new(text).parse().unwrap();
+        let built = ec! {
+            name = cat
+            addresses = (None (IpV4 (127.0.0.1)) (Index (3 -1)))
+            folders = (src/content src/static)
+            bind = Some(thing)
+        };
+        assert!(built.semantic_eq(&parsed), "{}\n{}", built.dump(), parsed.dump());
+    }
+
+    #[test]
+    fn interpolation() {
+        let port = 8080_u16;
+        let names = vec![1, 2];
+        assert_eq!(
+            ec! { port = {port} names = {names} },
+            Expression::list(vec![
+                Expression::binding("port", Expression::presence(8080_u16)),
+                Expression::binding("names", Expression::list(vec![
+                    Expression::presence(1),
+                    Expression::presence(2),
+                ])),
+            ])
+        );
+    }
+
+    #[test]
+    fn string_literals() {
+        assert_eq!(ec!("a=b"), Expression::presence(Atom::Quoted("a=b".to_string())));
+        assert!(ec!("two words").semantic_eq(&Parser::new("\"two words\"").parse().unwrap().get_first_expr_or_panic()));
+    }
+
+    #[test]
+    fn doc_comments() {
+        let expr = ec! {
+            /// The port.
+            port = 1
+        };
+        assert_eq!(expr, Expression::binding("port", Expression::presence(1)).with_comment("The port."));
+    }
+}
@@ -0,0 +1,61 @@
+use easy_config_derive::EasyConfig;
+
+#[derive(EasyConfig, PartialEq, Debug, Clone)]
+#[allow(dead_code)]
+struct Listener {
+    host: String,
+    port: u16,
+}
+
+#[derive(EasyConfig, PartialEq, Debug, Clone)]
+#[allow(dead_code)]
+struct Deployment {
+    name: String,
+    replicas: u32,
+    listener: Listener,
+    regions: Vec<String>,
+    backup: Option<u16>,
+}
+
+#[allow(dead_code)]
+fn deployment() -> Deployment {
+    Deployment {
+        name: "billing api".to_string(),
+        replicas: 3,
+        listener: Listener { host: "0.0.0.0".to_string(), port: 8080 },
+        regions: vec!["eu".to_string(), "us".to_string()],
+        backup: Some(9090),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use easy_config::env;
+    use easy_config::serialization::EasyConfig;
+    use super::*;
+
+    #[test]
+    fn keys() {
+        let keys: Vec<String> = env::flatten(&deployment(), "SVC").into_iter().map(|(k, _)| k).collect();
+        assert_eq!(keys, vec![
+            "SVC_NAME", "SVC_REPLICAS", "SVC_LISTENER__HOST", "SVC_LISTENER__PORT", "SVC_REGIONS", "SVC_BACKUP"
+        ]);
+    }
+
+    #[test]
+    fn round_trip_through_variables() {
+        let (exprs, source_text) = env::from_vars("SVC", env::flatten(&deployment(), "SVC")).unwrap();
+        assert_eq!(Deployment::deserialize(&mut exprs.into_iter(), &source_text).unwrap(), deployment());
+    }
+
+    #[test]
+    fn from_process_environment() {
+        for (key, value) in env::flatten(&deployment(), "EASY_CONFIG_ENV_TEST") {
+            // SAFETY: no other test reads or writes variables with this prefix.
+            unsafe { std::env::set_var(key, value) };
+        }
+
+        let (exprs, source_text) = env::from_env("EASY_CONFIG_ENV_TEST").unwrap();
+        assert_eq!(Deployment::deserialize(&mut exprs.into_iter(), &source_text).unwrap(), deployment());
+    }
+}
@@ -8,8 +8,6 @@ pub struct JustAGeneric<T: Ezc> {
 
 #[cfg(test)]
 mod tests {
-    use super::*;
-
     #[test]
     fn serialize() {
 
@@ -0,0 +1,16 @@
+use easy_config::parser::Parser;
+use easy_config::serialization::EasyConfig;
+use easy_config::serialization::serialization_error::SerializationError;
+
+// Read a `T` from `source` the way it would be read from a whole config file.
+pub fn parse<T: EasyConfig>(source: &str) -> Result<T, SerializationError> {
+    T::deserialize(&mut Parser::new(source).parse().unwrap().into_iter(), source)
+}
+
+// Write `value` out and read it back. A value written as a list is read from inside that list,
+// rather than from the list the whole source forms.
+pub fn round_trip<T: EasyConfig>(value: &T) -> T {
+    let text = value.serialize().dump();
+    let parsed = Parser::new(&text).parse().unwrap().into_iter().next().unwrap();
+    T::deserialize(&mut parsed.into_iter(), &text).unwrap()
+}
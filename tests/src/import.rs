@@ -0,0 +1,53 @@
+use easy_config_derive::EasyConfig;
+
+#[derive(EasyConfig, PartialEq, Debug)]
+#[allow(dead_code)]
+struct Database {
+    host: String,
+    port: u16,
+}
+
+#[derive(EasyConfig, PartialEq, Debug)]
+#[allow(dead_code)]
+struct Service {
+    name: String,
+    debug: bool,
+    database: Database,
+}
+
+#[cfg(test)]
+mod tests {
+    use easy_config::import::{dotenv, ini};
+    use easy_config::serialization::EasyConfig;
+    use super::*;
+
+    fn service() -> Service {
+        Service {
+            name: "billing api".to_string(),
+            debug: true,
+            database: Database { host: "db.internal".to_string(), port: 5432 },
+        }
+    }
+
+    #[test]
+    fn from_ini() {
+        let source = "name = \"billing api\"\ndebug = true\n\n[database]\nhost = db.internal\nport = 5432\n";
+        let exprs = ini::parse(source).unwrap();
+        assert_eq!(Service::deserialize(&mut exprs.into_iter(), source).unwrap(), service());
+    }
+
+    #[test]
+    fn from_dotenv() {
+        let source = "HOST=db.internal\nexport PORT=5432\n";
+        let exprs = dotenv::parse(source).unwrap();
+        assert_eq!(Database::deserialize(&mut exprs.into_iter(), source).unwrap(), service().database);
+    }
+
+    #[test]
+    fn errors_show_the_imported_line() {
+        let source = "name = api\ndebug = false\n[database]\nhost = db.internal\nport = 99999\n";
+        let exprs = ini::parse(source).unwrap();
+        let error = Service::deserialize(&mut exprs.into_iter(), source).unwrap_err().to_string();
+        assert!(error.contains("port = 99999"), "{}", error);
+    }
+}
@@ -1,7 +1,18 @@
+// Some derived types here only exist to check that the derive compiles for them.
+#![allow(dead_code)]
+
 mod named_fields;
 mod unnamed_fields;
 mod simple_enum;
 mod complex_enum;
 mod use_case;
 mod single_field_enum;
-mod has_a_generic;
\ No newline at end of file
+mod has_a_generic;
+mod ec_macro;
+mod serde_bridge;
+mod import;
+mod env_vars;
+mod map_keys;
+mod wrappers;
+#[cfg(test)]
+mod helpers;
@@ -0,0 +1,59 @@
+use std::collections::{BTreeMap, HashMap};
+use easy_config_derive::EasyConfig;
+
+#[derive(EasyConfig, Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[allow(dead_code)]
+enum Tier {
+    Free,
+    Paid,
+}
+
+#[derive(EasyConfig, Debug, Clone, PartialEq)]
+#[allow(dead_code)]
+struct Limits {
+    requests: u32,
+}
+
+#[derive(EasyConfig, Debug, Clone, PartialEq)]
+#[allow(dead_code)]
+struct Listener {
+    host: String,
+}
+
+#[derive(EasyConfig, Debug, Clone, PartialEq)]
+#[allow(dead_code)]
+struct Gateway {
+    listeners: HashMap<u16, Listener>,
+    limits: BTreeMap<Tier, Limits>,
+}
+
+#[cfg(test)]
+mod tests {
+    use easy_config::serialization::map_key::MapKey;
+    use easy_config::serialization::serialization_error::Kind;
+    use crate::helpers::{parse, round_trip};
+    use super::*;
+
+    #[test]
+    fn unit_enum_keys() {
+        assert_eq!(Tier::Paid.to_key(), "Paid");
+        assert_eq!(Tier::from_key("Free"), Some(Tier::Free));
+        assert_eq!(Tier::from_key("Gold"), None);
+    }
+
+    #[test]
+    fn read_keyed_maps() {
+        let source = "listeners = (8080 = (host = a) 9090 = (host = b)) limits = (Paid = (requests = 100) Free = (requests = 10))";
+        let gateway = parse::<Gateway>(source).unwrap();
+        assert_eq!(gateway.listeners[&9090], Listener { host: "b".to_string() });
+        assert_eq!(gateway.limits.keys().copied().collect::<Vec<_>>(), vec![Tier::Free, Tier::Paid]);
+
+        assert_eq!(round_trip(&gateway), gateway);
+    }
+
+    #[test]
+    fn unknown_enum_key() {
+        let error = parse::<BTreeMap<Tier, u32>>("Free = 1 Gold = 2").unwrap_err();
+        assert!(matches!(error.kind(), Kind::InvalidMapKey { key, .. } if key == "Gold"));
+    }
+}
@@ -17,8 +17,8 @@ fn testing() -> NamedFields {
 
 #[cfg(test)]
 mod tests {
-    use easy_config::expression::Expression;
-    use easy_config::parser::Parser;
+    use easy_config::expression::{Atom, Expression};
+    use crate::helpers::round_trip;
     use easy_config::serialization::EasyConfig;
 
     use super::*;
@@ -28,10 +28,7 @@ mod tests {
 
         assert_eq!(testing().serialize(), Expression::list(vec![
             Expression::presence("NamedFields"),
-            Expression::binding("x", Expression::list(vec![
-                Expression::presence("hello"),
-                Expression::presence("world"),
-            ])),
+            Expression::binding("x", Expression::presence(Atom::Quoted("hello world".to_string()))),
             Expression::binding("z", Expression::list(vec![
                 Expression::presence(1),
                 Expression::presence(2),
@@ -42,11 +39,6 @@ mod tests {
 
     #[test]
     fn deserialize() {
-        let exprs = testing().serialize();
-        let text = exprs.uncomented_dump();
-        let parsed = Parser::new(&text).parse().unwrap().into_iter().next().unwrap();
-        println!("{}", text);
-        let deserialized = NamedFields::deserialize(&mut parsed.into_iter(), text).expect("should deserialize");
-        assert_eq!(deserialized, testing());
+        assert_eq!(round_trip(&testing()), testing());
     }
 }
\ No newline at end of file
@@ -0,0 +1,152 @@
+use std::collections::HashMap;
+use easy_config_derive::EasyConfig;
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+struct Server {
+    name: String,
+    port: u16,
+    tags: Vec<u32>,
+    backup: Option<u16>,
+}
+
+#[derive(EasyConfig, PartialEq, Debug, Clone)]
+#[allow(dead_code)]
+struct Twin {
+    name: String,
+    port: u16,
+    tags: Vec<u32>,
+    backup: Option<u16>,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+#[allow(dead_code)]
+enum Shape {
+    Point,
+    Circle(u32),
+    Rect(u32, u32),
+    Poly { sides: u32, size: u32 },
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+#[allow(dead_code)]
+struct Meters(u32);
+
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+#[allow(dead_code)]
+struct Drawing {
+    shapes: Vec<Shape>,
+    limits: HashMap<String, u32>,
+    scale: Meters,
+}
+
+#[derive(Deserialize, PartialEq, Debug)]
+#[serde(untagged)]
+#[allow(dead_code)]
+enum Anything {
+    Number(i64),
+    Text(String),
+}
+
+#[allow(dead_code)]
+fn server() -> Server {
+    Server { name: "alpha".to_string(), port: 8080, tags: vec![1, 2, 3], backup: Some(8081) }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+    use easy_config::parser::Parser;
+    use easy_config::serialization::EasyConfig;
+    use easy_config::serialization::serialization_error::Kind;
+    use super::*;
+
+    #[test]
+    fn matches_derived_layout() {
+        let twin = Twin { name: "alpha".to_string(), port: 8080, tags: vec![1, 2, 3], backup: Some(8081) };
+        let mut serde_dump = easy_config::serde::to_expression(&server()).unwrap().dump();
+        serde_dump = serde_dump.replacen("Server", "Twin", 1);
+        assert_eq!(serde_dump, twin.serialize().dump());
+    }
+
+    #[test]
+    fn reads_derived_output() {
+        let twin = Twin { name: "alpha".to_string(), port: 8080, tags: vec![1, 2, 3], backup: None };
+        let text = twin.serialize().dump().replacen("Twin", "Server", 1);
+        let parsed = Parser::new(&text).parse().unwrap().into_iter().next().unwrap();
+        let result: Server = easy_config::serde::from_expressions(parsed.into_iter(), &text).unwrap();
+        assert_eq!(result, Server { backup: None, ..server() });
+    }
+
+    #[test]
+    fn struct_round_trip() {
+        let text = easy_config::serde::to_string_pretty(&server()).unwrap();
+        let result: Server = easy_config::serde::from_str(&text).unwrap();
+        assert_eq!(result, server());
+    }
+
+    #[test]
+    fn from_bare_bindings() {
+        let result: Server = easy_config::serde::from_str("name = alpha port = 8080 tags = (1 2 3) backup = (Some (8081))").unwrap();
+        assert_eq!(result, server());
+    }
+
+    #[test]
+    fn enums_maps_and_newtypes() {
+        let drawing = Drawing {
+            shapes: vec![
+                Shape::Point,
+                Shape::Circle(3),
+                Shape::Rect(4, 5),
+                Shape::Poly { sides: 6, size: 7 },
+            ],
+            limits: HashMap::from([("width".to_string(), 10), ("height".to_string(), 20)]),
+            scale: Meters(2),
+        };
+        let text = easy_config::serde::to_string_pretty(&drawing).unwrap();
+        let result: Drawing = easy_config::serde::from_str(&text).unwrap();
+        assert_eq!(result, drawing);
+    }
+
+    #[test]
+    fn missing_field() {
+        let err = easy_config::serde::from_str::<Server>("name = alpha tags = () backup = None").unwrap_err();
+        assert!(matches!(err.kind(), Kind::MissingField(field) if field == "port"));
+    }
+
+    #[test]
+    fn unknown_variant() {
+        let err = easy_config::serde::from_str::<Vec<Shape>>("(Hexagon ())").unwrap_err();
+        assert!(matches!(err.kind(), Kind::ExpectedDiscriminant(got, _) if got == "Hexagon"));
+    }
+
+    #[test]
+    fn keys_which_are_not_strings() {
+        let ports = HashMap::from([(8080_u16, "web".to_string()), (22, "ssh".to_string())]);
+        let text = easy_config::serde::to_string_pretty(&ports).unwrap();
+        assert_eq!(easy_config::serde::from_str::<HashMap<u16, String>>(&text).unwrap(), ports);
+        assert_eq!(easy_config::serde::from_str::<HashMap<u16, String>>("8080 = web 22 = ssh").unwrap(), ports);
+
+        let flags = BTreeMap::from([(true, 'y'), (false, 'n')]);
+        let text = easy_config::serde::to_string_pretty(&flags).unwrap();
+        assert_eq!(easy_config::serde::from_str::<BTreeMap<bool, char>>(&text).unwrap(), flags);
+
+        let initials = BTreeMap::from([('a', 1_u32), ('b', 2)]);
+        let text = easy_config::serde::to_string_pretty(&initials).unwrap();
+        assert_eq!(easy_config::serde::from_str::<BTreeMap<char, u32>>(&text).unwrap(), initials);
+    }
+
+    #[test]
+    fn invalid_key() {
+        let err = easy_config::serde::from_str::<HashMap<u8, String>>("300 = a").unwrap_err();
+        assert!(matches!(err.kind(), Kind::InvalidMapKey { key, target: "u8" } if key == "300"));
+    }
+
+    #[test]
+    fn untyped_integers_below_i128() {
+        assert_eq!(easy_config::serde::from_str::<Anything>("-5").unwrap(), Anything::Number(-5));
+
+        let err = easy_config::serde::from_str::<Anything>("-0xffffffffffffffffffffffffffffffff").unwrap_err();
+        assert!(matches!(err.kind(), Kind::IntegerOutOfRange { target: "i128", .. }));
+    }
+}
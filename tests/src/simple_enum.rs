@@ -10,7 +10,7 @@ enum EnumNoArgs {
 mod tests {
     use easy_config::serialization::EasyConfig;
     use easy_config::expression::Expression;
-    use easy_config::parser::Parser;
+    use crate::helpers::round_trip;
     use crate::simple_enum::EnumNoArgs;
 
     #[test]
@@ -23,11 +23,6 @@ mod tests {
 
     #[test]
     fn deserialize() {
-        let exprs = EnumNoArgs::One.serialize();
-        let text = exprs.uncomented_dump();
-        let parsed = Parser::new(&text).parse().unwrap().into_iter().next().unwrap();
-        println!("{}", text);
-        let deserialized = EnumNoArgs::deserialize(&mut parsed.into_iter(), text).expect("should deserialize");
-        assert_eq!(deserialized, EnumNoArgs::One)
+        assert_eq!(round_trip(&EnumNoArgs::One), EnumNoArgs::One);
     }
 }
\ No newline at end of file
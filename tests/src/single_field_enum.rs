@@ -10,7 +10,7 @@ enum TestEnum {
 mod tests {
     use easy_config::serialization::EasyConfig;
     use easy_config::expression::Expression;
-    use easy_config::parser::Parser;
+    use crate::helpers::parse;
     use super::*;
 
     #[test]
@@ -24,7 +24,7 @@ mod tests {
     #[test]
     fn deserialize() {
         let text = "(First (hello))";
-        let x = TestEnum::deserialize(&mut Parser::new(text).parse().unwrap().into_iter(), text).unwrap();
+        let x = parse::<TestEnum>(text).unwrap();
         assert_eq!(x, TestEnum::First("hello".to_string()));
     }
 }
\ No newline at end of file
@@ -14,19 +14,16 @@ fn testing() -> UnnamedFields {
 
 #[cfg(test)]
 mod tests {
-    use crate::unnamed_fields::{testing, UnnamedFields};
+    use crate::unnamed_fields::testing;
     use easy_config::serialization::EasyConfig;
-    use easy_config::expression::Expression;
-    use easy_config::parser::Parser;
+    use easy_config::expression::{Atom, Expression};
+    use crate::helpers::round_trip;
 
     #[test]
     fn serialize() {
         assert_eq!(testing().serialize(), Expression::list(vec![
             Expression::presence("UnnamedFields"),
-            Expression::list(vec![
-                Expression::presence("hello"),
-                Expression::presence("world"),
-            ]),
+            Expression::presence(Atom::Quoted("hello world".to_string())),
             Expression::list(vec![
                 Expression::presence(1),
                 Expression::presence(2),
@@ -37,11 +34,6 @@ mod tests {
 
     #[test]
     fn deserialize() {
-        let exprs = testing().serialize();
-        let text = exprs.uncomented_dump();
-        let parsed = Parser::new(&text).parse().unwrap().into_iter().next().unwrap();
-        println!("{}", text);
-        let deserialized = UnnamedFields::deserialize(&mut parsed.into_iter(), text).expect("should deserialize");
-        assert_eq!(deserialized, testing());
+        assert_eq!(round_trip(&testing()), testing());
     }
 }
\ No newline at end of file
@@ -0,0 +1,53 @@
+use std::rc::Rc;
+use std::sync::Arc;
+use easy_config_derive::EasyConfig;
+
+#[derive(EasyConfig, Debug, Clone, PartialEq)]
+#[allow(dead_code)]
+struct Node {
+    name: String,
+    next: Option<Box<Node>>,
+}
+
+#[derive(EasyConfig, Debug, Clone, PartialEq)]
+#[allow(dead_code)]
+enum Route {
+    Drop,
+    Forward,
+}
+
+#[derive(EasyConfig, Debug, Clone, PartialEq)]
+#[allow(dead_code)]
+struct Tls {
+    cert: String,
+}
+
+#[derive(EasyConfig, Debug, Clone, PartialEq)]
+#[allow(dead_code)]
+struct Server {
+    tls: Arc<Tls>,
+    routes: Vec<Rc<Route>>,
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::helpers::{parse, round_trip};
+    use super::*;
+
+    #[test]
+    fn recursive() {
+        let list = Node {
+            name: "a".to_string(),
+            next: Some(Box::new(Node { name: "b".to_string(), next: None })),
+        };
+        assert_eq!(round_trip(&list), list);
+    }
+
+    #[test]
+    fn shared_and_boxed_enums() {
+        let source = "tls = (cert = server.pem) routes = (Drop Forward)";
+        let server = parse::<Server>(source).unwrap();
+        assert_eq!(server.routes, vec![Rc::new(Route::Drop), Rc::new(Route::Forward)]);
+        assert_eq!(round_trip(&server), server);
+    }
+}